@@ -0,0 +1,46 @@
+//! Header verifier: a node serves its headers as a compact header stream and a light
+//! client checks linkage, proof of work and accumulated work without the block data,
+//! then checks one full block against its verified header.
+
+extern crate blockchainblock;
+
+use blockchainblock::*;
+
+fn mine(mut block: OwnedBlock<String>) -> OwnedBlock<String> {
+    while !block.meets_target() {
+        block.set_nonce(block.nonce() + 1);
+    }
+    block
+}
+
+fn main() {
+    let mut target = MAX_TARGET;
+    target[0] = 0x0f;
+
+    // full node
+    let mut chain : Vec<OwnedBlock<String>> = Vec::new();
+    for height in 0..5u64 {
+        let mut builder = BlockchainBlock::builder()
+            .timestamp(1524885322 + 60 * height)
+            .target(target)
+            .push(format!("payload {}", height));
+        if let Some(parent) = chain.last() {
+            builder = builder.parent(parent.header());
+        }
+        chain.push(mine(builder.build().into_owned()));
+    }
+    let stream = encode_headers(&chain.iter().map(|block| *block.header()).collect::<Vec<_>>());
+    println!("{} headers in {} bytes", chain.len(), stream.len());
+
+    // light client
+    let headers = decode_headers(&stream).expect("valid header stream");
+    assert!(verify::header_chain(&headers));
+    assert!(headers.iter().all(|header| header.meets_target()));
+    let work : Work = headers.iter().map(|header| header.work()).sum();
+    println!("chain work {}", work);
+
+    let block = OwnedBlock::<String>::from_bytes(&chain[3].to_bytes()).expect("valid block");
+    assert_eq!(block.header(), &headers[3]);
+    assert!(verify::body(&headers[3], block.body()));
+    println!("block at height {} matches its header: {:?}", block.height(), block.data());
+}
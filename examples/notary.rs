@@ -0,0 +1,47 @@
+//! Document notary: commit document digests into a chain of blocks, then prove a
+//! document was notarized in a given block.
+
+extern crate blockchainblock;
+
+use blockchainblock::*;
+
+fn document_digest(document: &str) -> BlockHash {
+    Hash256::digest(document.as_bytes()).into()
+}
+
+fn main() {
+    let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() }.with_derived_chain_id::<BlockHash>(&[]);
+    let mut chain : Vec<OwnedBlock<BlockHash>> = vec![BlockchainBlock::genesis(&params, &[]).into_owned()];
+
+    let batches = [
+        vec!["lease agreement, flat 3B", "invoice 2018-041"],
+        vec!["patent draft v2", "meeting minutes 2018-05-02", "invoice 2018-042"],
+    ];
+    for (round, batch) in batches.iter().enumerate() {
+        let digests : Vec<BlockHash> = batch.iter().map(|document| document_digest(document)).collect();
+        let parent = chain.last().unwrap().header();
+        let block = BlockchainBlock::builder()
+            .parent(parent)
+            .timestamp(params.genesis_timestamp + 600 * (round as u64 + 1))
+            .data(&digests)
+            .build()
+            .into_owned();
+        println!("{}\n", block);
+        chain.push(block);
+    }
+
+    let headers : Vec<BlockHeader> = chain.iter().map(|block| *block.header()).collect();
+    assert!(verify::header_chain(&headers));
+    assert!(chain.iter().all(|block| block.verify().is_ok()));
+
+    let document = "meeting minutes 2018-05-02";
+    let digest = document_digest(document);
+    for block in &chain {
+        if let Some(position) = block.iter().position(|item| *item == digest) {
+            assert!(block.check_fixed_value_inblock(&digest, position));
+            println!("\"{}\" notarized at height {}, time {}", document, block.height(), block.timestamp());
+        }
+    }
+    let forged = document_digest("meeting minutes 2018-05-03");
+    assert!(chain.iter().all(|block| !block.iter().any(|item| *item == forged)));
+}
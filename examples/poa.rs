@@ -0,0 +1,114 @@
+//! Proof-of-authority network: two nodes take turns producing blocks in a round-robin
+//! schedule and send them to each other over channels. Each block names its producer
+//! in a header extension, and a node only accepts a block from the authority whose turn
+//! it is. A real network would also sign the blocks; this example checks the schedule.
+
+extern crate blockchainblock;
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use blockchainblock::*;
+
+const AUTHORITIES: u64 = 2;
+const BLOCKS: u64 = 6;
+
+/// Authority that produced a block.
+struct Producer(u8);
+
+impl Byteable for Producer {
+    fn bytes(&self) -> Vec<u8> { vec![self.0] }
+}
+impl FromBytes for Producer {
+    fn from_bytes(bytes: &[u8]) -> Option<Producer> {
+        match bytes {
+            [id] => Some(Producer(*id)),
+            _ => None,
+        }
+    }
+}
+impl Extension for Producer {
+    const ID: u16 = 1;
+}
+
+type Chain = Blockchain<String, Extensions>;
+
+/// Authority whose turn it is to produce the block at `height`.
+fn scheduled(height: u64) -> u8 {
+    (height % AUTHORITIES) as u8
+}
+
+fn genesis(params: &ChainParams) -> SealedBlock<'static, String, Extensions> {
+    let block : OwnedBlock<String, Extensions> = BlockchainBlock::builder()
+        .extension(Extensions::new())
+        .chain_id(params.chain_id)
+        .timestamp(params.genesis_timestamp)
+        .push(String::from("genesis"))
+        .build()
+        .into_owned();
+    block.seal().expect("valid genesis block")
+}
+
+fn produce(chain: &Chain, id: u8) -> SealedBlock<'static, String, Extensions> {
+    let tip = chain.tip().expect("chain with a genesis block");
+    let mut extensions = Extensions::new();
+    extensions.insert(&Producer(id));
+    let block : OwnedBlock<String, Extensions> = BlockchainBlock::builder()
+        .extension(extensions)
+        .parent(tip.header())
+        .timestamp(tip.timestamp() + 5)
+        .push(format!("block {} of authority {}", tip.height() + 1, id))
+        .build()
+        .into_owned();
+    block.seal().expect("valid block")
+}
+
+fn accept(chain: &mut Chain, bytes: &[u8]) -> Result<(), String> {
+    let block : OwnedBlock<String, Extensions> = BlockchainBlock::from_bytes(bytes).map_err(|error| error.to_string())?;
+    let producer = block.extension().get::<Producer>().ok_or("block names no producer")?;
+    if producer.0 != scheduled(block.height()) {
+        return Err(format!("authority {} produced block {} out of turn", producer.0, block.height()));
+    }
+    let sealed = block.seal().map_err(|error| error.to_string())?;
+    chain.push(sealed).map_err(|error| error.to_string())?;
+    Ok(())
+}
+
+fn node(id: u8, params: ChainParams, inbox: Receiver<Vec<u8>>, peer: Sender<Vec<u8>>) -> Hash256 {
+    let mut chain : Chain = Blockchain::with_params(params);
+    chain.push(genesis(&params)).expect("empty chain");
+    while chain.len() as u64 <= BLOCKS {
+        let height = chain.len() as u64;
+        if scheduled(height) == id {
+            let block = produce(&chain, id);
+            println!("authority {} produced block {} {}", id, height, block.curr_hash());
+            peer.send(block.to_bytes()).expect("peer running");
+            chain.push(block).expect("block extending the tip");
+        } else {
+            let bytes = inbox.recv().expect("peer running");
+            accept(&mut chain, &bytes).expect("block of the scheduled authority");
+        }
+    }
+    chain.tip().expect("chain with a genesis block").curr_hash()
+}
+
+fn main() {
+    let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() }
+        .with_derived_chain_id(&[String::from("genesis")]);
+
+    let (to_first, first_inbox) = channel();
+    let (to_second, second_inbox) = channel();
+    let first = thread::spawn(move || node(0, params, first_inbox, to_second));
+    let second = thread::spawn(move || node(1, params, second_inbox, to_first));
+    let first_tip = first.join().expect("first node");
+    let second_tip = second.join().expect("second node");
+    assert_eq!(first_tip, second_tip);
+    println!("both nodes at tip {}", first_tip);
+
+    // authority 0 producing the block 1, scheduled for authority 1
+    let mut chain : Chain = Blockchain::with_params(params);
+    chain.push(genesis(&params)).expect("empty chain");
+    let out_of_turn = produce(&chain, 0);
+    let rejected = accept(&mut chain, &out_of_turn.to_bytes()).unwrap_err();
+    println!("rejected: {}", rejected);
+    assert_eq!(chain.len(), 1);
+}
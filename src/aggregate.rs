@@ -0,0 +1,135 @@
+//! Aggregate commitments.
+//!
+//! Chains anchoring privacy sensitive records, such as telemetry, can store statistics
+//! of the records instead of the records themselves. An [`Aggregate`] holds a count and
+//! a sum, the noise parameters the publisher declares having applied to them, and the
+//! merkle root of the hashes of the source blocks, so anyone holding the chain can
+//! check which blocks the statistics were computed from.
+//!
+//! The crate does not add the noise: it commits to the declared parameters, the
+//! publisher being responsible for the mechanism.
+
+use super::*;
+use crate::epoch;
+
+/// Length of the encoding of an [`Aggregate`].
+const AGGREGATELEN: usize = 4 + 8 * 6 + BLOCKHASHLEN;
+
+/// Noise declared by the publisher of an [`Aggregate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Noise {
+    /// privacy budget epsilon, in millionths
+    pub epsilon_ppm: u64,
+    /// largest change of the statistics a single record can cause
+    pub sensitivity: u64,
+}
+
+/// Statistics of the records of a range of blocks.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+/// let mut chain : Blockchain<BlockHash> = Blockchain::with_params(params);
+/// for block in fixtures::chain(&params, 7, 6) {
+///     chain.push(block.seal().unwrap()).unwrap();
+/// }
+///
+/// let noise = Noise { epsilon_ppm: 500_000, sensitivity: 1 };
+/// let aggregate = Aggregate::new(&chain, 1, 0, 4, 42, -3, noise).unwrap();
+/// assert!(aggregate.references(&chain, 6));
+/// assert!(!aggregate.references(&chain, 3));
+/// assert!(!Aggregate { first_height: 2, ..aggregate }.references(&chain, 6));
+/// assert!(Aggregate::new(&chain, 1, 3, 4, 42, -3, noise).is_none());
+///
+/// assert_eq!(Aggregate::from_bytes(&aggregate.bytes()), Some(aggregate));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Aggregate {
+    /// identifier of the statistic, chosen by the application
+    pub metric: u32,
+    /// height of the first source block
+    pub first_height: u64,
+    /// number of source blocks
+    pub len: u64,
+    /// merkle root of the hashes of the source blocks, from the first to the last
+    pub sources: Hash256,
+    /// number of records, noise included
+    pub count: u64,
+    /// sum of the records, noise included
+    pub sum: i64,
+    /// noise applied to `count` and `sum`
+    pub noise: Noise,
+}
+
+impl Aggregate {
+    /// Constructs the `Aggregate` of the `len` blocks of `chain` from height
+    /// `first_height`. Returns `None` when `len` is `0` or the chain does not hold all
+    /// the blocks.
+    pub fn new<T: Clone + 'static, H>(chain: &Blockchain<T, H>, metric: u32, first_height: u64, len: u64, count: u64, sum: i64, noise: Noise) -> Option<Aggregate> {
+        let blocks = epoch::blocks(chain, first_height, len)?;
+        Some(Aggregate { metric, first_height, len, sources: epoch::blocks_root(&blocks), count, sum, noise })
+    }
+
+    /// Check the aggregate, stored in the block of `chain` at `height`, references
+    /// source blocks of `chain` below that block.
+    pub fn references<T: Clone + 'static, H>(&self, chain: &Blockchain<T, H>, height: u64) -> bool {
+        let last = match self.len.checked_sub(1).and_then(|len| self.first_height.checked_add(len)) {
+            Some(last) => last,
+            None => return false,
+        };
+        if last >= height { return false; }
+        epoch::blocks(chain, self.first_height, self.len)
+            .is_some_and(|blocks| epoch::blocks_root(&blocks) == self.sources)
+    }
+}
+
+impl Byteable for Aggregate {
+    fn bytes(&self) -> Vec<u8> {
+        let mut out = vec![0; AGGREGATELEN];
+        self.write(&mut out);
+        out
+    }
+}
+
+impl FixedByteable for Aggregate {
+    const LEN: usize = AGGREGATELEN;
+    fn write(&self, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.metric.to_le_bytes());
+        out[4..12].copy_from_slice(&self.first_height.to_le_bytes());
+        out[12..20].copy_from_slice(&self.len.to_le_bytes());
+        out[20..52].copy_from_slice(self.sources.as_bytes());
+        out[52..60].copy_from_slice(&self.count.to_le_bytes());
+        out[60..68].copy_from_slice(&self.sum.to_le_bytes());
+        out[68..76].copy_from_slice(&self.noise.epsilon_ppm.to_le_bytes());
+        out[76..].copy_from_slice(&self.noise.sensitivity.to_le_bytes());
+    }
+}
+
+impl FromBytes for Aggregate {
+    fn from_bytes(bytes: &[u8]) -> Option<Aggregate> {
+        if bytes.len() != AGGREGATELEN { return None; }
+        let read = |start: usize| -> [u8; 8] {
+            let mut value = [0; 8];
+            value.copy_from_slice(&bytes[start..start + 8]);
+            value
+        };
+        let mut metric = [0; 4];
+        metric.copy_from_slice(&bytes[..4]);
+        Some(Aggregate {
+            metric: u32::from_le_bytes(metric),
+            first_height: u64::from_le_bytes(read(4)),
+            len: u64::from_le_bytes(read(12)),
+            sources: Hash256::from_bytes(&bytes[20..52])?,
+            count: u64::from_le_bytes(read(52)),
+            sum: i64::from_le_bytes(read(60)),
+            noise: Noise {
+                epsilon_ppm: u64::from_le_bytes(read(68)),
+                sensitivity: u64::from_le_bytes(read(76)),
+            },
+        })
+    }
+}
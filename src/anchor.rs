@@ -0,0 +1,155 @@
+//! Mutual anchoring of two chains.
+//!
+//! Two chains, such as a production chain and the chain of a regulator, periodically
+//! store an [`Anchor`] of the tip of the other chain in one of their blocks. Once a
+//! block of the other chain is anchored, rewriting it also means rewriting the chain
+//! holding the anchor: [`Anchor::check`] tells whether a chain still holds the block
+//! an anchor commits to.
+
+use std::fmt;
+use super::*;
+
+/// Length of the encoding of an [`Anchor`].
+const ANCHORLEN: usize = 4 + 8 + BLOCKHASHLEN;
+
+/// Errors returned when checking a chain against an [`Anchor`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnchorError {
+    /// The chain identifier of the chain is not the one of the anchor.
+    ChainMismatch(u32),
+    /// The chain has no block at the height of the anchor: it is behind or was
+    /// truncated.
+    Missing(u64),
+    /// The block of the chain at the height of the anchor is not the anchored block:
+    /// the history was rewritten.
+    Rewritten(u64),
+}
+
+impl fmt::Display for AnchorError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnchorError::ChainMismatch(chain_id) => write!(formatter, "anchor of chain {}", chain_id),
+            AnchorError::Missing(height) => write!(formatter, "no block at anchored height {}", height),
+            AnchorError::Rewritten(height) => write!(formatter, "anchored block at height {} was rewritten", height),
+        }
+    }
+}
+
+impl std::error::Error for AnchorError {}
+
+/// Commitment to a block of a chain, stored as an item of a block of another chain.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// fn genesis(chain_id: u32) -> SealedBlock<'static, Anchor> {
+///     let block : OwnedBlock<Anchor> = BlockchainBlock::builder().chain_id(chain_id).build().into_owned();
+///     block.seal().unwrap()
+/// }
+/// fn anchor(chain: &mut Blockchain<Anchor>, other: &Blockchain<Anchor>) {
+///     let tip = chain.tip().unwrap();
+///     let block : OwnedBlock<Anchor> = BlockchainBlock::builder()
+///         .parent(tip.header())
+///         .timestamp(tip.timestamp() + 1)
+///         .push(Anchor::of(other).unwrap())
+///         .build()
+///         .into_owned();
+///     chain.push(block.seal().unwrap()).unwrap();
+/// }
+///
+/// let mut production : Blockchain<Anchor> = Blockchain::new();
+/// let mut regulator : Blockchain<Anchor> = Blockchain::new();
+/// production.push(genesis(1)).unwrap();
+/// regulator.push(genesis(2)).unwrap();
+/// anchor(&mut regulator, &production);
+/// anchor(&mut production, &regulator);
+///
+/// // each chain checks the anchors the other one holds
+/// let anchors = |chain: &Blockchain<Anchor>| -> Vec<Anchor> {
+///     chain.iter().flat_map(|block| block.data().to_vec()).collect()
+/// };
+/// assert!(anchors(&regulator).iter().all(|anchor| anchor.check(&production).is_ok()));
+/// assert!(anchors(&production).iter().all(|anchor| anchor.check(&regulator).is_ok()));
+///
+/// // a production chain rewritten from its genesis block
+/// let mut rewritten : Blockchain<Anchor> = Blockchain::new();
+/// rewritten.push(BlockchainBlock::builder().chain_id(1).timestamp(5).build().into_owned().seal().unwrap()).unwrap();
+/// let anchored = anchors(&regulator)[0];
+/// assert_eq!(anchored.check(&rewritten), Err(AnchorError::Rewritten(0)));
+/// assert_eq!(anchored.check(&regulator), Err(AnchorError::ChainMismatch(1)));
+///
+/// let decoded = Anchor::from_bytes(&anchored.bytes()).unwrap();
+/// assert_eq!(decoded, anchored);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Anchor {
+    /// identifier of the anchored chain
+    pub chain_id: u32,
+    /// height of the anchored block
+    pub height: u64,
+    /// hash of the anchored block
+    pub hash: Hash256,
+}
+
+impl Anchor {
+    /// Constructs the `Anchor` of `block`.
+    pub fn new<T: Clone, H>(block: &BlockchainBlock<T, H>) -> Anchor {
+        Anchor { chain_id: block.header().chain_id, height: block.height(), hash: block.curr_hash() }
+    }
+
+    /// Constructs the `Anchor` of the tip of `chain`, `None` when the chain is empty.
+    pub fn of<T: Clone + 'static, H>(chain: &Blockchain<T, H>) -> Option<Anchor> {
+        chain.tip().map(|tip| Anchor::new(tip))
+    }
+
+    /// Check `chain` holds the anchored block at the anchored height.
+    ///
+    /// The chain is checked as it is: a chain behind the anchor fails with
+    /// `AnchorError::Missing` until it receives the anchored block.
+    pub fn check<T: Clone + 'static, H>(&self, chain: &Blockchain<T, H>) -> Result<(), AnchorError> {
+        let genesis = chain.genesis().ok_or(AnchorError::Missing(self.height))?;
+        if genesis.header().chain_id != self.chain_id {
+            return Err(AnchorError::ChainMismatch(self.chain_id));
+        }
+        match chain.at_height(self.height) {
+            None => Err(AnchorError::Missing(self.height)),
+            Some(block) if block.curr_hash() != self.hash => Err(AnchorError::Rewritten(self.height)),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+impl Byteable for Anchor {
+    fn bytes(&self) -> Vec<u8> {
+        let mut out = vec![0; ANCHORLEN];
+        self.write(&mut out);
+        out
+    }
+}
+
+impl FixedByteable for Anchor {
+    const LEN: usize = ANCHORLEN;
+    fn write(&self, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.chain_id.to_le_bytes());
+        out[4..12].copy_from_slice(&self.height.to_le_bytes());
+        out[12..].copy_from_slice(self.hash.as_bytes());
+    }
+}
+
+impl FromBytes for Anchor {
+    fn from_bytes(bytes: &[u8]) -> Option<Anchor> {
+        if bytes.len() != ANCHORLEN { return None; }
+        let mut chain_id = [0; 4];
+        chain_id.copy_from_slice(&bytes[..4]);
+        let mut height = [0; 8];
+        height.copy_from_slice(&bytes[4..12]);
+        Some(Anchor {
+            chain_id: u32::from_le_bytes(chain_id),
+            height: u64::from_le_bytes(height),
+            hash: Hash256::from_bytes(&bytes[12..])?,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+//! Base64 encoding (RFC 4648, standard alphabet with padding) of binary payloads
+//! for the JSON export format.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode `bytes` in padded base64.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = (u32::from(chunk[0]) << 16)
+            | (u32::from(*chunk.get(1).unwrap_or(&0)) << 8)
+            | u32::from(*chunk.get(2).unwrap_or(&0));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decode padded base64, `None` unless `text` is exactly what [`encode`] produces
+/// for some input: no whitespace, mandatory padding and zero unused bits.
+pub(crate) fn decode(text: &str) -> Option<Vec<u8>> {
+    fn sextet(c: u8) -> Option<u32> {
+        ALPHABET.iter().position(|a| *a == c).map(|position| position as u32)
+    }
+    let text = text.as_bytes();
+    if !text.len().is_multiple_of(4) { return None; }
+    let mut out : Vec<u8> = Vec::with_capacity(text.len() / 4 * 3);
+    let quads = text.len() / 4;
+    for (index, quad) in text.chunks(4).enumerate() {
+        let padding = quad.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 || padding > 0 && index + 1 != quads { return None; }
+        let mut group = 0;
+        for c in &quad[..4 - padding] {
+            group = group << 6 | sextet(*c)?;
+        }
+        group <<= 6 * padding;
+        let bytes = [(group >> 16) as u8, (group >> 8) as u8, group as u8];
+        let len = 3 - padding;
+        // unused bits of the last sextet must be zero for the encoding to be unique
+        if group & (0xff_ffff >> (8 * len)) != 0 { return None; }
+        out.extend_from_slice(&bytes[..len]);
+    }
+    Some(out)
+}
@@ -0,0 +1,67 @@
+use std::borrow::Cow;
+use super::*;
+use crate::merkle;
+
+/// Body of a block: the data committed to by the header merkle root.
+///
+/// The data is either borrowed from the caller or owned by the body.
+#[derive(Debug)]
+pub struct BlockBody<'a, T: Clone> {
+    /// encrypted data in compressed form
+    pub data: Cow<'a, [T]>,
+}
+
+impl<'a, T: Clone> BlockBody<'a, T> {
+    /// Constructs a new `BlockBody<T>` borrowing `data`.
+    pub fn new(data: &'a [T]) -> BlockBody<'a, T> {
+        BlockBody { data: Cow::Borrowed(data) }
+    }
+
+    /// Constructs a new `BlockBody<T>` owning `data`.
+    pub fn owned(data: Vec<T>) -> BlockBody<'a, T> {
+        BlockBody { data: Cow::Owned(data) }
+    }
+
+    /// Check the body holds no data.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Convert the body into one owning its data, cloning the data if it is borrowed.
+    pub fn into_owned(self) -> BlockBody<'static, T>
+    where
+        T: 'static
+    {
+        BlockBody { data: Cow::Owned(self.data.into_owned()) }
+    }
+}
+
+impl<'a, T: Byteable + Clone> BlockBody<'a, T> {
+    /// Calculate the merkle root of the data. `EMPTY_MERKLE_ROOT` for an empty body.
+    pub fn merkle_root(&self) -> Hash256 {
+        Hash256::new(merkle::merkle_root(&self.data))
+    }
+
+    /// Check the body is the one committed to by `header`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 2] = [5, 6];
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+    /// assert!(block.body().matches(block.header()));
+    /// assert!(!BlockBody::new(&data[..1]).matches(block.header()));
+    /// ```
+    pub fn matches<H>(&self, header: &BlockHeader<H>) -> bool {
+        self.merkle_root() == header.merkle_root
+    }
+}
+
+impl<'a, T: Clone> Clone for BlockBody<'a, T> {
+    fn clone(&self) -> BlockBody<'a, T> {
+        BlockBody { data: self.data.clone() }
+    }
+}
@@ -0,0 +1,249 @@
+use std::borrow::Cow;
+use super::*;
+
+/// Builder for configurable construction of a `BlockchainBlock<T>`.
+///
+/// Every field has a default: no previous hash, height, timestamp and nonce `0`, the
+/// current protocol `VERSION`, the easiest target `MAX_TARGET`, chain `0`, no header
+/// extension, no data and no size limit. The merkle root and the hash are calculated by
+/// [`build`](BlockchainBlockBuilder::build).
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 1] = [5];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::builder()
+///     .timestamp(4)
+///     .nonce(3)
+///     .data(&data)
+///     .build();
+/// assert_eq!(block.curr_hash(), BlockchainBlock::new(None, &data, 4, 3).curr_hash());
+///
+/// let child : BlockchainBlock<String> = BlockchainBlock::builder()
+///     .parent(block.header())
+///     .timestamp_now()
+///     .version(0)
+///     .push(String::from("first"))
+///     .push(String::from("second"))
+///     .build();
+/// assert_eq!(child.header().prev_hash, Some(block.curr_hash()));
+/// assert_eq!(child.header().height, 1);
+/// assert_eq!(child.header().version, 0);
+/// assert_eq!(child.data().len(), 2);
+///
+/// let extended : BlockchainBlock<i32, String> = BlockchainBlock::builder()
+///     .timestamp(4)
+///     .nonce(3)
+///     .extension(String::from("epoch 7"))
+///     .data(&data)
+///     .build();
+/// assert_eq!(extended.extension(), "epoch 7");
+/// assert_ne!(extended.curr_hash(), block.curr_hash());
+/// ```
+pub struct BlockchainBlockBuilder<'a, T: Clone, H = ()> {
+    prev_hash: Option<Hash256>,
+    // `None` when the parent is at the largest height
+    height: Option<u64>,
+    timestamp: u64,
+    nonce: u64,
+    version: u8,
+    target: BlockHash,
+    chain_id: u32,
+    max_block_bytes: usize,
+    extension: H,
+    data: Cow<'a, [T]>,
+}
+
+impl<'a, T> BlockchainBlockBuilder<'a, T>
+where
+    T: Byteable + Clone
+{
+    /// Constructs a new `BlockchainBlockBuilder<T>` with default values.
+    pub fn new() -> BlockchainBlockBuilder<'a, T> {
+        BlockchainBlockBuilder {
+            prev_hash: None,
+            height: Some(0),
+            timestamp: 0,
+            nonce: 0,
+            version: VERSION,
+            target: MAX_TARGET,
+            chain_id: 0,
+            max_block_bytes: usize::MAX,
+            extension: (),
+            data: Cow::Owned(Vec::new()),
+        }
+    }
+}
+
+impl<'a, T, H> BlockchainBlockBuilder<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable
+{
+    /// Set the hash of the previous block.
+    pub fn prev_hash(mut self, prev_hash: Hash256) -> BlockchainBlockBuilder<'a, T, H> {
+        self.prev_hash = Some(prev_hash);
+        self
+    }
+
+    /// Build on top of `parent`: link to its hash, take the next height, its chain and
+    /// its target.
+    /// A parent at the largest height has no next height, and
+    /// [`try_build`](BlockchainBlockBuilder::try_build) fails with `BlockError::HeightOverflow`.
+    pub fn parent<P: Byteable>(mut self, parent: &BlockHeader<P>) -> BlockchainBlockBuilder<'a, T, H> {
+        self.prev_hash = Some(parent.hash());
+        self.height = parent.height.checked_add(1);
+        self.chain_id = parent.chain_id;
+        self.target = parent.target;
+        self
+    }
+
+    /// Set the height of the block in the chain.
+    pub fn height(mut self, height: u64) -> BlockchainBlockBuilder<'a, T, H> {
+        self.height = Some(height);
+        self
+    }
+
+    /// Set the creation time in *Unix time* format.
+    pub fn timestamp(mut self, timestamp: u64) -> BlockchainBlockBuilder<'a, T, H> {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Set the creation time to the current system time.
+    pub fn timestamp_now(self) -> BlockchainBlockBuilder<'a, T, H> {
+        self.timestamp_from(&SystemClock)
+    }
+
+    /// Set the creation time to the current time of `clock`.
+    pub fn timestamp_from<C: Clock>(mut self, clock: &C) -> BlockchainBlockBuilder<'a, T, H> {
+        self.timestamp = clock.now();
+        self
+    }
+
+    /// Set the nonce.
+    pub fn nonce(mut self, nonce: u64) -> BlockchainBlockBuilder<'a, T, H> {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Create the block for the protocol version `version`.
+    pub fn protocol_version(mut self, version: ProtocolVersion) -> BlockchainBlockBuilder<'a, T, H> {
+        self.version = version.as_u8();
+        self
+    }
+
+    /// Override the protocol version number stamped in the header, known or not.
+    pub fn version(mut self, version: u8) -> BlockchainBlockBuilder<'a, T, H> {
+        self.version = version;
+        self
+    }
+
+    /// Set the difficulty target the block hash must not exceed.
+    pub fn target(mut self, target: BlockHash) -> BlockchainBlockBuilder<'a, T, H> {
+        self.target = target;
+        self
+    }
+
+    /// Set the identifier of the chain the block belongs to.
+    pub fn chain_id(mut self, chain_id: u32) -> BlockchainBlockBuilder<'a, T, H> {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Set the extension carried in the header.
+    pub fn extension<E: Byteable>(self, extension: E) -> BlockchainBlockBuilder<'a, T, E> {
+        BlockchainBlockBuilder {
+            prev_hash: self.prev_hash,
+            height: self.height,
+            timestamp: self.timestamp,
+            nonce: self.nonce,
+            version: self.version,
+            target: self.target,
+            chain_id: self.chain_id,
+            max_block_bytes: self.max_block_bytes,
+            extension,
+            data: self.data,
+        }
+    }
+
+    /// Limit the encoded size of the block checked by [`try_build`](BlockchainBlockBuilder::try_build).
+    pub fn max_block_bytes(mut self, max_block_bytes: usize) -> BlockchainBlockBuilder<'a, T, H> {
+        self.max_block_bytes = max_block_bytes;
+        self
+    }
+
+    /// Replace the data of the block with borrowed `data`.
+    pub fn data(mut self, data: &'a [T]) -> BlockchainBlockBuilder<'a, T, H> {
+        self.data = Cow::Borrowed(data);
+        self
+    }
+
+    /// Append one item to the data of the block.
+    /// Borrowed data is copied on the first append.
+    pub fn push(mut self, item: T) -> BlockchainBlockBuilder<'a, T, H> {
+        self.data.to_mut().push(item);
+        self
+    }
+
+    /// Calculate the merkle root and the hash and return the block.
+    /// Panics on a parent at the largest height, which
+    /// [`try_build`](BlockchainBlockBuilder::try_build) reports as an error instead.
+    pub fn build(self) -> BlockchainBlock<'a, T, H> {
+        let height = self.height.expect("parent at the largest height has no child");
+        let body = BlockBody { data: self.data };
+        let header = BlockHeader {
+            prev_hash: self.prev_hash,
+            height,
+            merkle_root: body.merkle_root(),
+            timestamp: self.timestamp,
+            nonce: self.nonce,
+            version: self.version,
+            target: self.target,
+            chain_id: self.chain_id,
+            extension: self.extension,
+        };
+        BlockchainBlock::from_parts(header, body)
+    }
+
+    /// Build the block as [`build`](BlockchainBlockBuilder::build) does, failing with
+    /// `BlockError::HeightOverflow` when the parent is at the largest height and with
+    /// `BlockError::TooLarge` when its encoded size exceeds the configured limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data = vec![String::from("x"); 100];
+    /// let params = ChainParams::default();
+    /// let block = BlockchainBlock::builder().max_block_bytes(params.max_block_bytes).data(&data).try_build();
+    /// assert!(block.is_ok());
+    /// let block = BlockchainBlock::builder().max_block_bytes(100).data(&data).try_build();
+    /// assert!(matches!(block, Err(BlockError::TooLarge(_))));
+    ///
+    /// let last : BlockchainBlock<String> = BlockchainBlock::builder().height(u64::MAX).build();
+    /// let block = BlockchainBlock::builder().parent(last.header()).data(&data).try_build();
+    /// assert_eq!(block, Err(BlockError::HeightOverflow));
+    /// ```
+    pub fn try_build(self) -> Result<BlockchainBlock<'a, T, H>, BlockError> {
+        if self.height.is_none() { return Err(BlockError::HeightOverflow); }
+        let max_block_bytes = self.max_block_bytes;
+        let block = self.build();
+        block.check_size(max_block_bytes)?;
+        Ok(block)
+    }
+}
+
+impl<'a, T> Default for BlockchainBlockBuilder<'a, T>
+where
+    T: Byteable + Clone
+{
+    fn default() -> BlockchainBlockBuilder<'a, T> {
+        BlockchainBlockBuilder::new()
+    }
+}
@@ -0,0 +1,559 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use super::*;
+
+/// Errors returned when appending a block to a chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainError {
+    /// The first block of the chain has a previous hash.
+    NotGenesis,
+    /// The block does not directly follow a block of the chain or of a side branch.
+    Unlinked,
+    /// The block is already in the chain or in a side branch.
+    Known,
+    /// The block target is not the one of the chain parameters.
+    BadTarget,
+    /// The block would start or extend a side branch more than `max_fork_depth` blocks
+    /// below the tip.
+    ForkTooDeep,
+    /// The side branches already hold `max_side_blocks` blocks.
+    SideBranchesFull,
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChainError::NotGenesis => write!(formatter, "first block of the chain is not a genesis block"),
+            ChainError::Unlinked => write!(formatter, "block does not follow a known block"),
+            ChainError::Known => write!(formatter, "block is already known"),
+            ChainError::BadTarget => write!(formatter, "block target is not the target of the chain"),
+            ChainError::ForkTooDeep => write!(formatter, "block is too far below the tip"),
+            ChainError::SideBranchesFull => write!(formatter, "side branches are full"),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+/// Change of the best chain: blocks rolled back from the old tip and blocks applied
+/// to reach the new one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReorgEvent {
+    /// hash of the last block common to both chains
+    pub fork_point: Hash256,
+    /// hashes of the blocks disconnected, from the old tip down to the fork point
+    pub disconnected: Vec<Hash256>,
+    /// hashes of the blocks connected, from the fork point up to the new tip
+    pub connected: Vec<Hash256>,
+    /// cost of rolling back the disconnected blocks, see
+    /// [`set_rollback_cost`](Blockchain::set_rollback_cost)
+    pub rollback_cost: u64,
+}
+
+/// Status of a block in a [`Blockchain`], see [`Blockchain::status_of`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockStatus {
+    /// The block is not known.
+    Unknown,
+    /// The block is in a side branch.
+    SideBranch,
+    /// The block is in the chain with fewer confirmations than required, given here.
+    Pending(u64),
+    /// The block is in the chain with at least the required confirmations.
+    Confirmed,
+}
+
+/// Cost for a state machine of rolling back a block of a [`Blockchain`].
+pub type RollbackCost<T, H> = fn(&BlockchainBlock<'static, T, H>) -> u64;
+
+/// Chain of blocks linked by their hashes, from the genesis block to the tip.
+///
+/// Only sealed blocks are accepted, see [`SealedBlock`], and every block must follow
+/// a known block as [`BlockHeader::follows`] defines it: linked to its hash, at the
+/// next height and of the same chain.
+///
+/// Every block must have the target of the [`ChainParams`] of the chain, and being
+/// sealed its hash meets that target: a block cannot claim more work than it was mined
+/// for. Blocks that do not extend the tip are kept in side branches. The chain follows
+/// the branch with the most accumulated [`Work`], keeping the current tip on ties; with
+/// a constant target this is the longest branch. As a tie never rolls back the chain,
+/// the branch of equal work needing no rollback is always the one kept.
+///
+/// Side branches are bounded by the parameters: they hold at most `max_side_blocks`
+/// blocks, and blocks more than `max_fork_depth` below the tip are rejected or, as the
+/// tip advances, dropped. A reorganization moves the blocks it disconnects to the side
+/// branches even when they are full.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+/// let genesis : OwnedBlock<String> = BlockchainBlock::genesis(&params, &[String::from("genesis")]).into_owned();
+/// let mut chain : Blockchain<String> = Blockchain::new();
+/// chain.push(genesis.seal().unwrap()).unwrap();
+///
+/// let data = [String::from("first")];
+/// let block : BlockchainBlock<String> = BlockchainBlock::builder()
+///     .parent(chain.tip().unwrap().header())
+///     .timestamp(1524885382)
+///     .data(&data)
+///     .build();
+/// let sealed = block.seal().unwrap().into_owned();
+/// chain.push(sealed.clone()).unwrap();
+/// assert_eq!(chain.len(), 2);
+/// assert_eq!(chain.tip(), Some(&sealed));
+/// assert_eq!(chain.genesis().unwrap().height(), 0);
+///
+/// assert_eq!(chain.push(sealed), Err(ChainError::Known));
+/// ```
+pub struct Blockchain<T: Clone + 'static, H = ()> {
+    params: ChainParams,
+    blocks: Vec<SealedBlock<'static, T, H>>,
+    /// position of the blocks of the chain, keyed by block hash
+    index: HashMap<Hash256, usize>,
+    /// blocks of the side branches, keyed by block hash and linked by `prev_hash`
+    side: HashMap<Hash256, SealedBlock<'static, T, H>>,
+    rollback_cost: RollbackCost<T, H>,
+    /// number of reorganizations, keyed by number of disconnected blocks
+    reorg_depths: BTreeMap<usize, u64>,
+}
+
+impl<T: Clone + 'static, H> Blockchain<T, H> {
+    /// Constructs an empty `Blockchain` with the default [`ChainParams`].
+    pub fn new() -> Blockchain<T, H> {
+        Blockchain::with_params(ChainParams::default())
+    }
+
+    /// Constructs an empty `Blockchain` for the chain described by `params`.
+    pub fn with_params(params: ChainParams) -> Blockchain<T, H> {
+        Blockchain {
+            params,
+            blocks: Vec::new(),
+            index: HashMap::new(),
+            side: HashMap::new(),
+            rollback_cost: |_| 1,
+            reorg_depths: BTreeMap::new(),
+        }
+    }
+
+    /// Set the cost of rolling back a block, reported in `ReorgEvent::rollback_cost`.
+    /// By default every block costs `1`, so the cost is the number of disconnected
+    /// blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// fn child(parent: &BlockHeader, data: Vec<i32>) -> SealedBlock<'static, i32> {
+    ///     let block : OwnedBlock<i32> = BlockchainBlock::builder()
+    ///         .parent(parent)
+    ///         .timestamp(parent.timestamp + 1)
+    ///         .data(&data)
+    ///         .build()
+    ///         .into_owned();
+    ///     block.seal().unwrap()
+    /// }
+    ///
+    /// // undoing a block costs one unit of state per item
+    /// let genesis = BlockchainBlock::new_owned(None, vec![0], 4, 3).seal().unwrap();
+    /// let mut chain : Blockchain<i32> = Blockchain::new();
+    /// chain.set_rollback_cost(|block| block.data().len() as u64);
+    /// chain.push(genesis.clone()).unwrap();
+    /// let a1 = child(genesis.header(), vec![1, 2, 3]);
+    /// chain.push(a1.clone()).unwrap();
+    ///
+    /// let b1 = child(genesis.header(), vec![4]);
+    /// chain.push(b1.clone()).unwrap();
+    /// let reorg = chain.push(child(b1.header(), vec![5])).unwrap().unwrap();
+    /// assert_eq!(reorg.rollback_cost, 3);
+    ///
+    /// // a branch of equal work never rolls the chain back
+    /// chain.push(child(a1.header(), vec![6])).unwrap();
+    /// assert_eq!(chain.tip().unwrap().data(), [5]);
+    /// assert_eq!(chain.reorg_depths().get(&1), Some(&1));
+    /// ```
+    pub fn set_rollback_cost(&mut self, rollback_cost: RollbackCost<T, H>) {
+        self.rollback_cost = rollback_cost;
+    }
+
+    /// Number of reorganizations of the chain, keyed by number of disconnected blocks.
+    /// The depths are at most `max_fork_depth + 1`, which bounds the distribution.
+    pub fn reorg_depths(&self) -> &BTreeMap<usize, u64> {
+        &self.reorg_depths
+    }
+
+    /// Parameters of the chain.
+    pub fn params(&self) -> &ChainParams {
+        &self.params
+    }
+
+    /// Return the first block of the chain.
+    pub fn genesis(&self) -> Option<&SealedBlock<'static, T, H>> {
+        self.blocks.first()
+    }
+
+    /// Return the last block of the chain.
+    pub fn tip(&self) -> Option<&SealedBlock<'static, T, H>> {
+        self.blocks.last()
+    }
+
+    /// Number of blocks in the chain.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Check the chain has no blocks yet.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Find the block with hash `hash` in the chain or in a side branch.
+    pub fn block(&self, hash: &Hash256) -> Option<&SealedBlock<'static, T, H>> {
+        match self.index.get(hash) {
+            Some(position) => self.blocks.get(*position),
+            None => self.side.get(hash),
+        }
+    }
+
+    /// Return the block of the chain at height `height`.
+    pub fn at_height(&self, height: u64) -> Option<&SealedBlock<'static, T, H>> {
+        let genesis = self.genesis()?.height();
+        let position = usize::try_from(height.checked_sub(genesis)?).ok()?;
+        self.blocks.get(position)
+    }
+
+    /// Check the block with hash `hash` is in the chain, not in a side branch.
+    pub fn contains(&self, hash: &Hash256) -> bool {
+        self.index.contains_key(hash)
+    }
+
+    /// Number of confirmations of the block with hash `hash`: `1` for the tip, one more
+    /// for each block after it, `0` in a side branch. Returns `None` for unknown blocks.
+    pub fn confirmations(&self, hash: &Hash256) -> Option<u64> {
+        match self.index.get(hash) {
+            Some(position) => Some((self.blocks.len() - position) as u64),
+            None => self.side.get(hash).map(|_| 0),
+        }
+    }
+
+    /// Status of the block with hash `hash`, confirmed once it has the
+    /// `confirmations_required` of the chain parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// fn child(parent: &BlockHeader, value: i32) -> SealedBlock<'static, i32> {
+    ///     let block : OwnedBlock<i32> = BlockchainBlock::builder()
+    ///         .parent(parent)
+    ///         .timestamp(parent.timestamp + 1)
+    ///         .data(&[value])
+    ///         .build()
+    ///         .into_owned();
+    ///     block.seal().unwrap()
+    /// }
+    ///
+    /// let params = ChainParams { confirmations_required: 2, ..ChainParams::default() };
+    /// let genesis = BlockchainBlock::new_owned(None, vec![0], 4, 3).seal().unwrap();
+    /// let mut chain : Blockchain<i32> = Blockchain::with_params(params);
+    /// chain.push(genesis.clone()).unwrap();
+    /// let a1 = child(genesis.header(), 1);
+    /// let b1 = child(genesis.header(), 2);
+    /// chain.push(a1.clone()).unwrap();
+    /// chain.push(b1.clone()).unwrap();
+    ///
+    /// assert_eq!(chain.confirmations(&genesis.curr_hash()), Some(2));
+    /// assert_eq!(chain.status_of(&genesis.curr_hash()), BlockStatus::Confirmed);
+    /// assert_eq!(chain.status_of(&a1.curr_hash()), BlockStatus::Pending(1));
+    /// assert_eq!(chain.status_of(&b1.curr_hash()), BlockStatus::SideBranch);
+    /// assert_eq!(chain.status_of(&Hash256::ZERO), BlockStatus::Unknown);
+    ///
+    /// chain.push(child(a1.header(), 3)).unwrap();
+    /// assert_eq!(chain.status_of(&a1.curr_hash()), BlockStatus::Confirmed);
+    /// ```
+    pub fn status_of(&self, hash: &Hash256) -> BlockStatus {
+        match self.confirmations(hash) {
+            None => BlockStatus::Unknown,
+            Some(0) => BlockStatus::SideBranch,
+            Some(confirmations) if confirmations < self.params.confirmations_required => BlockStatus::Pending(confirmations),
+            Some(_) => BlockStatus::Confirmed,
+        }
+    }
+
+    /// Accumulated work of the blocks of the chain.
+    pub fn work(&self) -> Work {
+        self.blocks.iter().map(|block| block.work()).sum()
+    }
+
+    /// Iterate over the blocks from the tip to the genesis block.
+    /// Use `rev()` to iterate from the genesis block to the tip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let mut chain : Blockchain<i32> = Blockchain::new();
+    /// chain.push(BlockchainBlock::new_owned(None, vec![1], 4, 3).seal().unwrap()).unwrap();
+    /// for value in 2..5 {
+    ///     let block : OwnedBlock<i32> = BlockchainBlock::builder()
+    ///         .parent(chain.tip().unwrap().header())
+    ///         .timestamp(4 + value as u64)
+    ///         .data(&[value])
+    ///         .build()
+    ///         .into_owned();
+    ///     chain.push(block.seal().unwrap()).unwrap();
+    /// }
+    ///
+    /// let heights : Vec<u64> = chain.iter().map(|block| block.height()).collect();
+    /// assert_eq!(heights, [3, 2, 1, 0]);
+    /// let values : Vec<i32> = chain.iter().rev().map(|block| block.data()[0]).collect();
+    /// assert_eq!(values, [1, 2, 3, 4]);
+    /// assert_eq!(chain.iter().take(2).map(|block| block.data()[0]).sum::<i32>(), 7);
+    /// ```
+    pub fn iter(&self) -> Blocks<'_, T, H> {
+        Blocks { blocks: self.blocks.iter() }
+    }
+}
+
+/// Iterator over the blocks of a [`Blockchain`], from the tip to the genesis block.
+pub struct Blocks<'c, T: Clone + 'static, H> {
+    blocks: std::slice::Iter<'c, SealedBlock<'static, T, H>>,
+}
+
+impl<'c, T: Clone + 'static, H> Iterator for Blocks<'c, T, H> {
+    type Item = &'c BlockchainBlock<'static, T, H>;
+
+    fn next(&mut self) -> Option<&'c BlockchainBlock<'static, T, H>> {
+        self.blocks.next_back().map(SealedBlock::block)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.blocks.size_hint()
+    }
+}
+
+impl<'c, T: Clone + 'static, H> DoubleEndedIterator for Blocks<'c, T, H> {
+    fn next_back(&mut self) -> Option<&'c BlockchainBlock<'static, T, H>> {
+        self.blocks.next().map(SealedBlock::block)
+    }
+}
+
+impl<'c, T: Clone + 'static, H> ExactSizeIterator for Blocks<'c, T, H> {}
+
+impl<'c, T: Clone + 'static, H> IntoIterator for &'c Blockchain<T, H> {
+    type Item = &'c BlockchainBlock<'static, T, H>;
+    type IntoIter = Blocks<'c, T, H>;
+
+    fn into_iter(self) -> Blocks<'c, T, H> {
+        self.iter()
+    }
+}
+
+impl<T: Byteable + Clone + 'static, H: Byteable> Blockchain<T, H> {
+    /// Add `block` to the chain or to a side branch.
+    ///
+    /// # Description
+    ///
+    /// A block following the tip is appended to the chain. A block following any other
+    /// known block is kept in a side branch, unless its branch then has more work than
+    /// the chain: the blocks of the chain after the fork point move to a side branch,
+    /// the blocks of the branch are applied and the `ReorgEvent` is returned.
+    ///
+    /// Fails with `ChainError::NotGenesis` when the chain is empty and `block` has a
+    /// previous hash, `ChainError::Unlinked` when `block` does not follow a known block,
+    /// `ChainError::Known` when `block` was already added, `ChainError::BadTarget`
+    /// when the target of `block` is not the one of the chain parameters,
+    /// `ChainError::ForkTooDeep` when `block` does not extend the tip and is more than
+    /// `max_fork_depth` blocks below it, and `ChainError::SideBranchesFull` when `block`
+    /// would be kept in full side branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// fn child(parent: &BlockHeader, value: i32) -> SealedBlock<'static, i32> {
+    ///     let block : OwnedBlock<i32> = BlockchainBlock::builder()
+    ///         .parent(parent)
+    ///         .timestamp(parent.timestamp + 1)
+    ///         .data(&[value])
+    ///         .build()
+    ///         .into_owned();
+    ///     block.seal().unwrap()
+    /// }
+    ///
+    /// let genesis = BlockchainBlock::new_owned(None, vec![0], 4, 3).seal().unwrap();
+    /// let mut chain : Blockchain<i32> = Blockchain::new();
+    /// assert_eq!(chain.push(genesis.clone()), Ok(None));
+    ///
+    /// let a1 = child(genesis.header(), 1);
+    /// let b1 = child(genesis.header(), 2);
+    /// let b2 = child(b1.header(), 3);
+    /// assert_eq!(chain.push(a1.clone()), Ok(None));
+    /// assert_eq!(chain.push(b1.clone()), Ok(None));
+    /// assert_eq!(chain.tip(), Some(&a1));
+    ///
+    /// let reorg = chain.push(b2.clone()).unwrap().unwrap();
+    /// assert_eq!(reorg.fork_point, genesis.curr_hash());
+    /// assert_eq!(reorg.disconnected, [a1.curr_hash()]);
+    /// assert_eq!(reorg.connected, [b1.curr_hash(), b2.curr_hash()]);
+    /// assert_eq!(chain.tip(), Some(&b2));
+    /// assert_eq!(chain.len(), 3);
+    /// assert!(!chain.contains(&a1.curr_hash()));
+    /// assert_eq!(chain.block(&a1.curr_hash()), Some(&a1));
+    ///
+    /// // an unmined block cannot claim the work of a harder target
+    /// let claimed : OwnedBlock<i32> = BlockchainBlock::builder()
+    ///     .parent(genesis.header())
+    ///     .target([0; BLOCKHASHLEN])
+    ///     .timestamp(5)
+    ///     .data(&[4])
+    ///     .build()
+    ///     .into_owned();
+    /// assert_eq!(claimed.seal(), Err(BlockError::TargetNotMet));
+    ///
+    /// let mut target = MAX_TARGET;
+    /// target[0] = 0x7f;
+    /// let mut harder : OwnedBlock<i32> = BlockchainBlock::builder()
+    ///     .parent(genesis.header())
+    ///     .target(target)
+    ///     .timestamp(5)
+    ///     .data(&[4])
+    ///     .build()
+    ///     .into_owned();
+    /// while !harder.meets_target() {
+    ///     harder.set_nonce(harder.nonce() + 1);
+    /// }
+    /// assert_eq!(chain.push(harder.seal().unwrap()), Err(ChainError::BadTarget));
+    /// ```
+    ///
+    /// With side branches limited to one block, one block deep:
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// fn child(parent: &BlockHeader, value: i32) -> SealedBlock<'static, i32> {
+    ///     let block : OwnedBlock<i32> = BlockchainBlock::builder()
+    ///         .parent(parent)
+    ///         .timestamp(parent.timestamp + 1)
+    ///         .data(&[value])
+    ///         .build()
+    ///         .into_owned();
+    ///     block.seal().unwrap()
+    /// }
+    ///
+    /// let params = ChainParams { max_fork_depth: 1, max_side_blocks: 1, ..ChainParams::default() };
+    /// let genesis = BlockchainBlock::new_owned(None, vec![0], 4, 3).seal().unwrap();
+    /// let mut chain : Blockchain<i32> = Blockchain::with_params(params);
+    /// chain.push(genesis.clone()).unwrap();
+    /// let a1 = child(genesis.header(), 1);
+    /// let a2 = child(a1.header(), 2);
+    /// chain.push(a1.clone()).unwrap();
+    /// chain.push(a2.clone()).unwrap();
+    ///
+    /// let b1 = child(genesis.header(), 3);
+    /// assert_eq!(chain.push(b1.clone()), Ok(None));
+    /// assert_eq!(chain.push(child(genesis.header(), 4)), Err(ChainError::SideBranchesFull));
+    ///
+    /// chain.push(child(a2.header(), 5)).unwrap();
+    /// assert_eq!(chain.block(&b1.curr_hash()), None);
+    /// assert_eq!(chain.push(child(genesis.header(), 6)), Err(ChainError::ForkTooDeep));
+    /// ```
+    pub fn push(&mut self, block: SealedBlock<'static, T, H>) -> Result<Option<ReorgEvent>, ChainError> {
+        let hash = block.curr_hash();
+        if self.block(&hash).is_some() { return Err(ChainError::Known); }
+        if block.target() != self.params.target { return Err(ChainError::BadTarget); }
+        let prev_hash = match (block.header().prev_hash, self.tip()) {
+            (None, None) => {
+                self.connect(block);
+                return Ok(None);
+            },
+            (Some(_), None) => return Err(ChainError::NotGenesis),
+            (None, Some(_)) => return Err(ChainError::Unlinked),
+            (Some(prev_hash), Some(_)) => prev_hash,
+        };
+        let parent = self.block(&prev_hash).ok_or(ChainError::Unlinked)?;
+        if !block.header().follows(parent.header()) { return Err(ChainError::Unlinked); }
+        if self.tip().map(|tip| tip.curr_hash()) == Some(prev_hash) {
+            self.connect(block);
+            self.prune();
+            return Ok(None);
+        }
+        let tip_height = self.tip().map_or(0, |tip| tip.height());
+        if tip_height.saturating_sub(block.height()) > self.params.max_fork_depth {
+            return Err(ChainError::ForkTooDeep);
+        }
+
+        // walk the side branch back to the chain
+        let mut branch : Vec<Hash256> = Vec::new();
+        let mut branch_work = block.work();
+        let mut fork_point = prev_hash;
+        while let Some(side) = self.side.get(&fork_point) {
+            branch.push(fork_point);
+            branch_work = branch_work.saturating_add(&side.work());
+            match side.header().prev_hash {
+                Some(prev_hash) => fork_point = prev_hash,
+                None => break,
+            }
+        }
+        let fork = *self.index.get(&fork_point).ok_or(ChainError::Unlinked)?;
+        let chain_work : Work = self.blocks[fork + 1..].iter().map(|block| block.work()).sum();
+        if branch_work <= chain_work {
+            if self.side.len() >= self.params.max_side_blocks { return Err(ChainError::SideBranchesFull); }
+            self.side.insert(hash, block);
+            return Ok(None);
+        }
+
+        let mut disconnected : Vec<Hash256> = Vec::new();
+        let mut rollback_cost : u64 = 0;
+        for old in self.blocks.split_off(fork + 1).into_iter().rev() {
+            let old_hash = old.curr_hash();
+            rollback_cost = rollback_cost.saturating_add((self.rollback_cost)(&old));
+            self.index.remove(&old_hash);
+            self.side.insert(old_hash, old);
+            disconnected.push(old_hash);
+        }
+        let mut connected : Vec<Hash256> = Vec::new();
+        for side_hash in branch.into_iter().rev() {
+            if let Some(side) = self.side.remove(&side_hash) {
+                self.connect(side);
+                connected.push(side_hash);
+            }
+        }
+        self.connect(block);
+        connected.push(hash);
+        self.prune();
+        *self.reorg_depths.entry(disconnected.len()).or_insert(0) += 1;
+        Ok(Some(ReorgEvent { fork_point, disconnected, connected, rollback_cost }))
+    }
+
+    /// Drop the side branch blocks more than `max_fork_depth` blocks below the tip.
+    fn prune(&mut self) {
+        let tip_height = self.tip().map_or(0, |tip| tip.height());
+        let max_fork_depth = self.params.max_fork_depth;
+        self.side.retain(|_, side| tip_height.saturating_sub(side.height()) <= max_fork_depth);
+    }
+
+    fn connect(&mut self, block: SealedBlock<'static, T, H>) {
+        self.index.insert(block.curr_hash(), self.blocks.len());
+        self.blocks.push(block);
+    }
+}
+
+impl<T: Clone + 'static, H> Default for Blockchain<T, H> {
+    fn default() -> Blockchain<T, H> {
+        Blockchain::new()
+    }
+}
@@ -1,26 +1,48 @@
-use crypto_hash::{Algorithm, digest};
+use std::borrow::Cow;
 use std::fmt;
 use super::*;
+use crate::{hex, merkle};
 
 /// Structure for storing one Block of the Blockchain with as few dependencies as possible.
-pub struct BlockchainBlock<'a, T>{
+///
+/// A block is made of a [`BlockHeader`] and a [`BlockBody`]. Its hash is calculated
+/// from the header only; the body is committed to through the header merkle root.
+///
+/// The data is either borrowed from the caller or owned by the block. Owned blocks
+/// ([`OwnedBlock`]) have no lifetime constraint, so they can be kept in long-lived
+/// collections and sent between threads.
+///
+/// Fields are private so the hash always matches the header and the merkle root
+/// always matches the data: mutation goes through methods such as
+/// [`set_nonce`](BlockchainBlock::set_nonce) and [`set_data`](BlockchainBlock::set_data)
+/// which recalculate both.
+///
+/// The header carries an optional extension of type `H`, hashed with the other header
+/// fields, for additional consensus data. It defaults to `()`, which adds nothing to the
+/// hash.
+pub struct BlockchainBlock<'a, T: Clone, H = ()>{
     /// hash of the current block
-    pub curr_hash: BlockHash,
-    /// hash of the previous block. Is `None` for the first block
-    pub prev_hash: Option<BlockHash>,
-    /// encrypted data in compressed form
-    pub data: &'a[T],
-    /// time of block creation in seconds since 1970-01-01T00:00 UTC
-    pub timestamp: u64,
-    /// field used for giving variability
-    pub nonce: u64,
-    /// root of a sha256 hash tree where the leaves are transactions
-    pub merkle_root: BlockHash,
-    /// version of the protocol used to create the block
-    pub version: u8,
+    curr_hash: Hash256,
+    /// header fields, hashed into `curr_hash`
+    header: BlockHeader<H>,
+    /// data of the block
+    body: BlockBody<'a, T>,
 }
 
-/// Implementation of BlockchainBlock for a generic type `T`
+/// Block owning its data, free of any borrow.
+pub type OwnedBlock<T, H = ()> = BlockchainBlock<'static, T, H>;
+
+impl<'a, T: Clone + 'static, H: Clone> From<&BlockchainBlock<'a, T, H>> for OwnedBlock<T, H> {
+    fn from(block: &BlockchainBlock<'a, T, H>) -> OwnedBlock<T, H> {
+        BlockchainBlock {
+            curr_hash: block.curr_hash,
+            header: block.header.clone(),
+            body: BlockBody::owned(block.body.data.to_vec()),
+        }
+    }
+}
+
+/// Constructors of blocks without header extension
 impl<'a, T> BlockchainBlock<'a, T>
 where
     T: Byteable + Clone
@@ -43,13 +65,13 @@ where
     ///   extern crate blockchainblock;
     ///   use crate::blockchainblock::*;
     ///   
-    ///   let prev  : Option<BlockHash> = None;
+    ///   let prev  : Option<Hash256> = None;
     ///   let nonce : u64 = 3;
     ///   let timestamp : u64 = 4;
     ///   let data : [i32; 1] = [5];
     ///   let block : BlockchainBlock<i32> = BlockchainBlock::new(prev, &data, timestamp, nonce);
     ///   println!("\n{:?}\n", &block);
-    ///   assert_eq!(block.curr_hash, [23, 105, 91, 179, 190, 192, 178, 189, 198, 134, 87, 143, 214, 135, 93, 17, 50, 143, 192, 3, 254, 144, 115, 123, 42, 223, 197, 199, 181, 113, 224, 123]);
+    ///   assert_eq!(block.curr_hash(), [179, 71, 94, 233, 32, 225, 148, 68, 197, 21, 73, 184, 144, 193, 56, 137, 58, 167, 154, 74, 20, 230, 219, 32, 159, 131, 127, 228, 103, 254, 151, 215]);
     /// ```
     ///
     /// Example with array of Strings
@@ -74,38 +96,278 @@ where
     ///    \"The Adventures of Sherlock Holmes\": \"Eye lyked it alot.\",
     ///    }")
     /// ];
-     
-    /// let prev  : Option<BlockHash> = Some([1; BLOCKHASHLEN]);
+    ///
+    /// let prev  : Option<Hash256> = Some(Hash256::new([1; BLOCKHASHLEN]));
     /// let nonce : u64 = 3;
     /// let timestamp = std::time::Duration::from_secs(1524885322).as_secs();
     /// let block : BlockchainBlock<String> = BlockchainBlock::new(prev, &book_reviews, timestamp, nonce);
     ///   
     /// println!("\n{:?}\n", &block);
-    /// assert_eq!(block.curr_hash, [220, 149, 236, 219, 173, 29, 131, 71, 35, 245, 97, 228, 58, 247, 45, 86, 197, 104, 26, 236, 232, 98, 144, 4, 220, 210, 177, 17, 235, 113, 214, 18]);
+    /// assert_eq!(block.curr_hash(), [216, 71, 149, 237, 117, 137, 111, 165, 94, 2, 94, 130, 213, 205, 7, 97, 28, 36, 11, 120, 9, 227, 210, 245, 107, 72, 206, 22, 77, 10, 155, 227]);
+    /// ```
+    pub fn new(prev_hash: Option<Hash256>, data: &[T], timestamp: u64, nonce: u64) -> BlockchainBlock<'_, T> {
+        BlockchainBlock::from_body(prev_hash, BlockBody::new(data), timestamp, nonce)
+    }
+
+    /// Constructs a new `BlockchainBlock<T>` owning its data.
+    ///
+    /// # Description
+    ///
+    /// * `prev_hash` - Hash of the previous block.
+    /// * `data` - Data to be stored in the block.
+    /// * `timestamp` - Creation time in *Unix time* format.
+    /// * `nonce` - Nonce to include variability in the hash calculation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let block : OwnedBlock<i32> = BlockchainBlock::new_owned(None, vec![5], 4, 3);
+    /// let hash = std::thread::spawn(move || block.curr_hash()).join().unwrap();
+    /// assert_eq!(hash, BlockchainBlock::new(None, &[5], 4, 3).curr_hash());
+    /// ```
+    pub fn new_owned(prev_hash: Option<Hash256>, data: Vec<T>, timestamp: u64, nonce: u64) -> OwnedBlock<T>
+    where
+        T: 'static
+    {
+        BlockchainBlock::from_body(prev_hash, BlockBody::owned(data), timestamp, nonce)
+    }
+
+    /// Constructs a new `BlockchainBlock<T>` for the chain described by `params`,
+    /// validating the inputs instead of building an invalid block.
+    ///
+    /// # Description
+    ///
+    /// * `params` - Parameters of the chain the block belongs to.
+    /// * `parent` - Header of the previous block, `None` for the genesis block.
+    /// * `data` - Data to be stored in the block.
+    /// * `timestamp` - Creation time in *Unix time* format.
+    /// * `nonce` - Nonce to include variability in the hash calculation.
+    /// * `clock` - Source of the local time the timestamp is checked against.
+    ///
+    /// The block links to the hash of `parent` at the next height, with the target of
+    /// `params`. It is returned sealed, see [`SealedBlock`].
+    ///
+    /// Fails with `MissingPrevHash` when there is no parent and the block does not hash
+    /// to the genesis block of `params`, `ChainMismatch` when `parent` belongs to another
+    /// chain, `HeightOverflow` when `parent` is at the largest height, `EmptyData` when
+    /// `data` is empty and the chain does not allow empty blocks, `BadTimestamp` when the
+    /// timestamp is before the genesis block or more than `max_time_drift` ahead of
+    /// `clock`, `TargetNotMet` when the hash does not meet the target and `TooLarge`
+    /// when the encoded block exceeds `max_block_bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 1] = [5];
+    /// let params = ChainParams { genesis_timestamp: 1524885322, allow_empty_blocks: false, ..ChainParams::default() }
+    ///     .with_genesis_hash(&data);
+    /// let clock = FixedClock(1524885400);
+    /// let genesis = BlockchainBlock::try_new(&params, None, &data, 1524885322, 0, &clock).unwrap();
+    /// assert_eq!(*genesis, BlockchainBlock::genesis(&params, &data));
+    ///
+    /// let parent = Some(genesis.header());
+    /// let child = BlockchainBlock::try_new(&params, parent, &data, 1524885382, 0, &clock).unwrap();
+    /// assert!(child.header().follows(genesis.header()));
+    /// let mut chain : Blockchain<i32> = Blockchain::new();
+    /// assert_eq!(chain.push(genesis.clone().into_owned()), Ok(None));
+    /// assert_eq!(chain.push(child.into_owned()), Ok(None));
+    /// assert_eq!(chain.len(), 2);
+    /// assert_eq!(BlockchainBlock::try_new(&params, None, &data, 1524885382, 0, &clock), Err(BlockError::MissingPrevHash));
+    /// assert_eq!(BlockchainBlock::try_new(&params, None, &[6], 1524885322, 0, &clock), Err(BlockError::MissingPrevHash));
+    /// assert_eq!(BlockchainBlock::<i32>::try_new(&params, parent, &[], 1524885382, 0, &clock), Err(BlockError::EmptyData));
+    /// assert_eq!(BlockchainBlock::try_new(&params, parent, &data, 4, 0, &clock), Err(BlockError::BadTimestamp(4)));
+    /// assert_eq!(BlockchainBlock::try_new(&params, parent, &data, 1524892601, 0, &clock), Err(BlockError::BadTimestamp(1524892601)));
+    ///
+    /// let testnet = ChainParams { chain_id: 8, ..params };
+    /// assert_eq!(BlockchainBlock::try_new(&testnet, parent, &data, 1524885382, 0, &clock), Err(BlockError::ChainMismatch(0)));
+    /// let hard = ChainParams { target: [0; BLOCKHASHLEN], ..params };
+    /// assert_eq!(BlockchainBlock::try_new(&hard, parent, &data, 1524885382, 0, &clock), Err(BlockError::TargetNotMet));
+    /// ```
+    pub fn try_new<'d, C: Clock>(params: &ChainParams, parent: Option<&BlockHeader>, data: &'d [T], timestamp: u64, nonce: u64, clock: &C) -> Result<SealedBlock<'d, T>, BlockError> {
+        let body = BlockBody::new(data);
+        let mut header = BlockHeader::new(parent.map(BlockHeader::hash), body.merkle_root(), timestamp, nonce);
+        header.chain_id = params.chain_id;
+        header.target = params.target;
+        match parent {
+            Some(parent) => {
+                header.height = parent.height.checked_add(1).ok_or(BlockError::HeightOverflow)?;
+                if !header.follows(parent) { return Err(BlockError::ChainMismatch(parent.chain_id)); }
+            },
+            None => {
+                let genesis = params.genesis_hash.unwrap_or_else(|| BlockchainBlock::genesis(params, data).curr_hash());
+                if header.hash() != genesis { return Err(BlockError::MissingPrevHash); }
+            },
+        }
+        if data.is_empty() && !params.allow_empty_blocks { return Err(BlockError::EmptyData); }
+        if timestamp < params.genesis_timestamp || timestamp > clock.now().saturating_add(params.max_time_drift) {
+            return Err(BlockError::BadTimestamp(timestamp));
+        }
+        let block = BlockchainBlock::from_parts(header, body);
+        if !block.meets_target() { return Err(BlockError::TargetNotMet); }
+        block.check_size(params.max_block_bytes)?;
+        Ok(SealedBlock::new_unchecked(block))
+    }
+
+    /// Constructs a new `BlockchainBlock<T>` created at the current system time.
+    ///
+    /// # Description
+    ///
+    /// * `prev_hash` - Hash of the previous block.
+    /// * `data` - Data to be stored in the block.
+    /// * `nonce` - Nonce to include variability in the hash calculation.
+    pub fn new_now(prev_hash: Option<Hash256>, data: &[T], nonce: u64) -> BlockchainBlock<'_, T> {
+        BlockchainBlock::new_with_clock(prev_hash, data, nonce, &SystemClock)
+    }
+
+    /// Constructs a new `BlockchainBlock<T>` created at the current time of `clock`.
+    ///
+    /// # Description
+    ///
+    /// * `prev_hash` - Hash of the previous block.
+    /// * `data` - Data to be stored in the block.
+    /// * `nonce` - Nonce to include variability in the hash calculation.
+    /// * `clock` - Source of the creation time.
+    ///
+    /// # Examples
+    ///
     /// ```
-   
-    pub fn new(prev_hash: Option<BlockHash>, data: &[T], timestamp: u64, nonce: u64) -> BlockchainBlock<T> {
-        let mut block = BlockchainBlock {
-            prev_hash,
-            data,
-            timestamp, 
-            merkle_root : [ 0; BLOCKHASHLEN],
-            nonce,
-            version : VERSION,
-            curr_hash : [ 0; BLOCKHASHLEN]
-        };
-        if data.len() > 0 { block.merkle_root = block.calculate_merkle_root(data); }
-        block.calculate_hash();        
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 1] = [5];
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new_with_clock(None, &data, 3, &FixedClock(4));
+    /// assert_eq!(block, BlockchainBlock::new(None, &data, 4, 3));
+    ///
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new_now(None, &data, 3);
+    /// assert!(block.timestamp() >= 1524885322);
+    /// ```
+    pub fn new_with_clock<'d, C: Clock>(prev_hash: Option<Hash256>, data: &'d [T], nonce: u64, clock: &C) -> BlockchainBlock<'d, T> {
+        BlockchainBlock::from_body(prev_hash, BlockBody::new(data), clock.now(), nonce)
+    }
+
+    /// Constructs a new `BlockchainBlock<T>` without data, its merkle root being
+    /// `EMPTY_MERKLE_ROOT`.
+    ///
+    /// # Description
+    ///
+    /// * `prev_hash` - Hash of the previous block.
+    /// * `timestamp` - Creation time in *Unix time* format.
+    /// * `nonce` - Nonce to include variability in the hash calculation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::empty(None, 4, 3);
+    /// assert!(block.is_empty());
+    /// assert!(block.header().is_empty());
+    /// assert_eq!(block, BlockchainBlock::new(None, &[], 4, 3));
+    /// assert_eq!(block.verify(), Ok(()));
+    /// ```
+    pub fn empty(prev_hash: Option<Hash256>, timestamp: u64, nonce: u64) -> BlockchainBlock<'a, T> {
+        BlockchainBlock::from_body(prev_hash, BlockBody::owned(Vec::new()), timestamp, nonce)
+    }
+
+    /// Constructs the genesis block of the chain described by `params`.
+    ///
+    /// # Description
+    ///
+    /// * `params` - Parameters of the chain, giving the chain identifier, the target and
+    ///   the genesis timestamp and nonce. With a target harder than `MAX_TARGET`, the
+    ///   genesis nonce must be mined for the block to [`seal`](BlockchainBlock::seal).
+    /// * `data` - Data to be stored in the block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let params = ChainParams { genesis_timestamp: 1524885322, chain_id: 7, ..ChainParams::default() };
+    /// let data = [String::from("genesis")];
+    /// let genesis : BlockchainBlock<String> = BlockchainBlock::genesis(&params, &data);
+    /// assert!(genesis.header().is_genesis());
+    /// assert_eq!(genesis.chain_id(), 7);
+    /// assert_eq!(genesis, BlockchainBlock::genesis(&params, &data));
+    ///
+    /// let testnet = ChainParams { chain_id: 8, ..params };
+    /// assert_ne!(genesis, BlockchainBlock::genesis(&testnet, &data));
+    /// ```
+    pub fn genesis<'d>(params: &ChainParams, data: &'d [T]) -> BlockchainBlock<'d, T> {
+        let body = BlockBody::new(data);
+        let mut header = BlockHeader::new(None, body.merkle_root(), params.genesis_timestamp, params.genesis_nonce);
+        header.chain_id = params.chain_id;
+        header.target = params.target;
+        BlockchainBlock::from_parts(header, body)
+    }
+
+    fn from_body(prev_hash: Option<Hash256>, body: BlockBody<'a, T>, timestamp: u64, nonce: u64) -> BlockchainBlock<'a, T> {
+        let header = BlockHeader::new(prev_hash, body.merkle_root(), timestamp, nonce);
+        BlockchainBlock::from_parts(header, body)
+    }
+
+    /// Start building a block with [`BlockchainBlockBuilder`].
+    pub fn builder() -> BlockchainBlockBuilder<'a, T> {
+        BlockchainBlockBuilder::new()
+    }
+}
+
+/// Implementation of BlockchainBlock for a generic type `T`
+impl<'a, T, H> BlockchainBlock<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable
+{
+    /// Assemble a block from a header and a body, calculating its hash.
+    /// The body is not checked against the header merkle root, see [`BlockBody::matches`].
+    pub fn from_parts(header: BlockHeader<H>, body: BlockBody<'a, T>) -> BlockchainBlock<'a, T, H> {
+        let mut block = BlockchainBlock { curr_hash: Hash256::ZERO, header, body };
+        block.calculate_hash();
         block
     }
 
+    /// Convert the block into one owning its data, cloning the data if it is borrowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let mut chain : Vec<OwnedBlock<String>> = Vec::new();
+    /// {
+    ///     let data = [String::from("short lived")];
+    ///     let block : BlockchainBlock<String> = BlockchainBlock::new(None, &data, 4, 3);
+    ///     chain.push(block.into_owned());
+    /// }
+    /// assert_eq!(chain[0].data()[0], "short lived");
+    /// ```
+    pub fn into_owned(self) -> OwnedBlock<T, H>
+    where
+        T: 'static
+    {
+        BlockchainBlock {
+            curr_hash: self.curr_hash,
+            header: self.header,
+            body: self.body.into_owned(),
+        }
+    }
+
     /// Check data is inside the block.
     /// Calculate a merkle root and compare it with the one stored in the block.
     ///
     /// # Description
     ///
     /// * `data` - Data to be checked.
-    /// * `position` - Position of the data in the original array. 0 <= pos < block.data.len()
+    /// * `position` - Position of the data in the original array. 0 <= pos < block.data().len()
     ///
     /// # Examples
     ///    
@@ -130,7 +392,7 @@ where
     ///     ),
     /// ];
     /// 
-    /// let prev  : Option<BlockHash> = None;
+    /// let prev  : Option<Hash256> = None;
     /// let nonce : u64 = 1;
     /// let timestamp = std::time::Duration::from_secs(1524885322).as_secs();
     /// let block : BlockchainBlock<String> = BlockchainBlock::new(prev, &book_reviews, timestamp, nonce);
@@ -138,124 +400,419 @@ where
     /// println!("\n{:?}\n", &block);
     /// assert_eq!(block.check_value_inblock(&string_check,2), true);
     /// ```
-
     pub fn check_value_inblock(&self, data: &T, position: usize) -> bool{
-        if position >= self.data.len(){ return false; }
-        let mut temp = self.data.to_vec();
+        if position >= self.body.data.len(){ return false; }
+        let mut temp = self.body.data.to_vec();
         temp[position] = data.clone();
-        if self.calculate_merkle_root(&temp[..]) == self.merkle_root { return true; }
-        else{ return false; }
-    }
-    
-    fn calculate_merkle_hash<'b>(&self, block_left: &'b BlockHash, block_right: &'b BlockHash) -> BlockHash{
-        const DOUBLE_BLOCK_LEN : usize = BLOCKHASHLEN * 2;
-        let mut bytes: [u8; DOUBLE_BLOCK_LEN] = [0; DOUBLE_BLOCK_LEN];
-        
-        bytes[..BLOCKHASHLEN].clone_from_slice(block_left);
-        bytes[BLOCKHASHLEN..].clone_from_slice(block_right);
-        let digest = digest(Algorithm::SHA256, &bytes);
-        let mut result: BlockHash = [0; BLOCKHASHLEN];
-        result.copy_from_slice(&digest);
-        return result;
-    }
-
-    fn calculate_merkle_root (&self, blocks: &[T]) -> BlockHash{
-        
-        let size = blocks.len();
-        match size {
-            1 | 2 => {
-                let mut bytes : Vec<u8> = Vec::new();
-                if size == 1 {
-                    bytes.append(&mut blocks[0].bytes());
-                    bytes.append(&mut blocks[0].bytes());
-                } else {
-                    bytes.append(&mut blocks[0].bytes());
-                    bytes.append(&mut blocks[1].bytes());
-                }
-                let digest = digest(Algorithm::SHA256, &bytes);
-                let mut result: BlockHash = [0; BLOCKHASHLEN];
-                result.copy_from_slice(&digest);
-                return result;
-            },
-            _ => {
-                let (left, right) = blocks.split_at(size/2);
-                return self.calculate_merkle_hash(
-                    &self.calculate_merkle_root(&left),
-                    &self.calculate_merkle_root(&right));
-            },
-        }
+        self.header.merkle_root == merkle::merkle_root(&temp[..])
+    }
+
+    /// Set the nonce and recalculate the hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 1] = [5];
+    /// let mut block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 0);
+    /// block.set_nonce(3);
+    /// assert_eq!(block.nonce(), 3);
+    /// assert_eq!(block, BlockchainBlock::new(None, &data, 4, 3));
+    /// ```
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.header.nonce = nonce;
+        self.calculate_hash();
+    }
+
+    /// Set the creation time and recalculate the hash.
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.header.timestamp = timestamp;
+        self.calculate_hash();
+    }
+
+    /// Replace the data, recalculating the merkle root and the hash.
+    ///
+    /// # Description
+    ///
+    /// * `data` - Borrowed slice or owned vector with the new data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let mut block : BlockchainBlock<i32> = BlockchainBlock::new(None, &[1], 4, 3);
+    /// block.set_data(vec![5]);
+    /// assert_eq!(block.data(), &[5]);
+    /// assert_eq!(block, BlockchainBlock::new(None, &[5], 4, 3));
+    /// ```
+    pub fn set_data<D: Into<Cow<'a, [T]>>>(&mut self, data: D) {
+        self.body = BlockBody { data: data.into() };
+        self.header.merkle_root = self.body.merkle_root();
+        self.calculate_hash();
+    }
+
+    /// Check the block hash matches its header and the header merkle root matches the body.
+    pub fn is_consistent(&self) -> bool {
+        self.header.hash() == self.curr_hash && self.body.matches(&self.header)
+    }
+
+    /// Verify the block: recalculate the merkle root from the data and the hash from
+    /// the header, compare them with the stored ones and check the hash meets the
+    /// difficulty target.
+    ///
+    /// # Description
+    ///
+    /// Fails with `BadVersion` when the header version is not the current protocol
+    /// `VERSION` (see [`validate_version`](BlockchainBlock::validate_version) to accept
+    /// other versions), `MerkleMismatch` when the data does not match the merkle root,
+    /// `HashMismatch` when the stored hash does not match the header and `TargetNotMet`
+    /// when the block was not mined for its target, see
+    /// [`meets_target`](BlockchainBlock::meets_target). A block without data must have
+    /// `EMPTY_MERKLE_ROOT` as merkle root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 2] = [5, 6];
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+    /// assert_eq!(block.verify(), Ok(()));
+    ///
+    /// let tampered : BlockchainBlock<i32> = BlockchainBlock::from_parts(*block.header(), BlockBody::new(&data[..1]));
+    /// assert_eq!(tampered.verify(), Err(BlockError::MerkleMismatch));
+    ///
+    /// let old : BlockchainBlock<i32> = BlockchainBlock::builder().version(0).data(&data).build();
+    /// assert_eq!(old.verify(), Err(BlockError::BadVersion(0)));
+    ///
+    /// let empty : BlockchainBlock<i32> = BlockchainBlock::new(None, &[], 4, 3);
+    /// assert!(empty.is_empty());
+    /// assert_eq!(empty.merkle_root(), EMPTY_MERKLE_ROOT);
+    /// assert_eq!(empty.verify(), Ok(()));
+    /// assert_ne!(BlockchainBlock::new(None, &[String::new()], 4, 3).merkle_root(), EMPTY_MERKLE_ROOT);
+    /// let zeroed = BlockHeader { merkle_root: Hash256::ZERO, ..*empty.header() };
+    /// let zeroed : BlockchainBlock<i32> = BlockchainBlock::from_parts(zeroed, BlockBody::new(&[]));
+    /// assert_eq!(zeroed.verify(), Err(BlockError::MerkleMismatch));
+    ///
+    /// let unmined : BlockchainBlock<i32> = BlockchainBlock::builder().target([0; BLOCKHASHLEN]).data(&data).build();
+    /// assert_eq!(unmined.verify(), Err(BlockError::TargetNotMet));
+    /// ```
+    pub fn verify(&self) -> Result<(), BlockError> {
+        self.validate_version(&VersionPolicy::default())?;
+        if !self.body.matches(&self.header) { return Err(BlockError::MerkleMismatch); }
+        if self.header.hash() != self.curr_hash { return Err(BlockError::HashMismatch); }
+        if !self.meets_target() { return Err(BlockError::TargetNotMet); }
+        Ok(())
+    }
+}
+
+/// Accessors available for any data type.
+impl<'a, T: Clone, H> BlockchainBlock<'a, T, H> {
+    /// Hash of the block.
+    pub fn curr_hash(&self) -> Hash256 {
+        self.curr_hash
+    }
+
+    /// Header of the block.
+    pub fn header(&self) -> &BlockHeader<H> {
+        &self.header
+    }
+
+    /// Body of the block.
+    pub fn body(&self) -> &BlockBody<'a, T> {
+        &self.body
+    }
+
+    /// Data stored in the block.
+    pub fn data(&self) -> &[T] {
+        &self.body.data
+    }
+
+    /// Check the block holds no data. Its merkle root is then `EMPTY_MERKLE_ROOT`.
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Number of data items in the block.
+    pub fn len(&self) -> usize {
+        self.body.data.len()
+    }
+
+    /// Iterate over the data items in the block.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.body.data.iter()
+    }
+
+    /// Data item at `index` with the index of its leaf in the merkle tree,
+    /// or `None` when `index` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 3] = [5, 6, 7];
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+    /// assert_eq!(block.len(), 3);
+    /// assert_eq!(block.iter().sum::<i32>(), 18);
+    /// assert_eq!(block.get(1), Some((&6, 1)));
+    /// assert_eq!(block.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<(&T, usize)> {
+        // leaves are the data items in order
+        self.body.data.get(index).map(|item| (item, index))
+    }
+
+    /// Hash of the previous block. Is `None` for the first block.
+    pub fn prev_hash(&self) -> Option<Hash256> {
+        self.header.prev_hash
+    }
+
+    /// Check the version of the block is accepted by `policy`.
+    /// Fails with `BlockError::BadVersion` otherwise.
+    pub fn validate_version(&self, policy: &VersionPolicy) -> Result<(), BlockError> {
+        policy.validate(self.header.version).map(|_| ())
+    }
+
+    /// Number of blocks before this one in the chain.
+    pub fn height(&self) -> u64 {
+        self.header.height
+    }
+
+    /// Root of the merkle tree of the data.
+    pub fn merkle_root(&self) -> Hash256 {
+        self.header.merkle_root
+    }
+
+    /// Creation time in seconds since 1970-01-01T00:00 UTC.
+    pub fn timestamp(&self) -> u64 {
+        self.header.timestamp
+    }
+
+    /// Nonce of the block.
+    pub fn nonce(&self) -> u64 {
+        self.header.nonce
+    }
+
+    /// Version of the protocol used to create the block.
+    pub fn version(&self) -> u8 {
+        self.header.version
+    }
+
+    /// Identifier of the chain the block belongs to.
+    pub fn chain_id(&self) -> u32 {
+        self.header.chain_id
+    }
+
+    /// Extension of the header.
+    pub fn extension(&self) -> &H {
+        &self.header.extension
+    }
+
+    /// Difficulty target of the block.
+    pub fn target(&self) -> BlockHash {
+        self.header.target
+    }
+
+    /// Check the block hash does not exceed the difficulty target, both read as
+    /// 256-bit big-endian integers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let mut target : BlockHash = [0; BLOCKHASHLEN];
+    /// target[0] = 0x0f;
+    /// let mut block : BlockchainBlock<i32> = BlockchainBlock::builder().target(target).push(5).build();
+    /// while !block.meets_target() {
+    ///     block.set_nonce(block.nonce() + 1);
+    /// }
+    /// assert!(block.curr_hash().as_bytes()[0] < 0x10);
+    /// assert!(BlockchainBlock::new(None, &[5], 4, 3).meets_target());
+    /// ```
+    pub fn meets_target(&self) -> bool {
+        self.curr_hash.as_bytes() <= &self.header.target
+    }
+
+    /// Work needed to meet the difficulty target of the block, see [`Work`].
+    pub fn work(&self) -> Work {
+        Work::from_target(&self.header.target)
+    }
+
+    /// Compare the work of two blocks.
+    pub fn cmp_work<'b, E>(&self, other: &BlockchainBlock<'b, T, E>) -> std::cmp::Ordering {
+        self.work().cmp(&other.work())
     }
-    
 }
 
-impl<'a, T: fmt::Debug> fmt::Debug for BlockchainBlock<'a, T>{
+/// Fast path for data types with a fixed-size representation.
+///
+/// Leaves are written into stack buffers, so no allocation happens per leaf. The resulting blocks are identical to
+/// the ones built through [`Byteable`] as long as `FixedByteable::write` produces the
+/// same bytes as `Byteable::bytes`.
+impl<'a, T> BlockchainBlock<'a, T>
+where
+    T: FixedByteable + Byteable + Clone
+{
+    /// Constructs a new `BlockchainBlock<T>` using the fixed-size hashing path.
+    ///
+    /// # Description
+    ///
+    /// * `prev_hash` - Hash of the previous block.
+    /// * `data` - Data to be stored in the block.
+    /// * `timestamp` - Creation time in *Unix time* format.
+    /// * `nonce` - Nonce to include variability in the hash calculation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let digests : [BlockHash; 3] = [[1; BLOCKHASHLEN], [2; BLOCKHASHLEN], [3; BLOCKHASHLEN]];
+    /// let fast : BlockchainBlock<BlockHash> = BlockchainBlock::new_fixed(None, &digests, 4, 3);
+    /// let slow : BlockchainBlock<BlockHash> = BlockchainBlock::new(None, &digests, 4, 3);
+    /// assert_eq!(fast.curr_hash(), slow.curr_hash());
+    /// assert!(fast.check_fixed_value_inblock(&[2; BLOCKHASHLEN], 1));
+    /// ```
+    pub fn new_fixed(prev_hash: Option<Hash256>, data: &[T], timestamp: u64, nonce: u64) -> BlockchainBlock<'_, T> {
+        let header = BlockHeader::new(prev_hash, Hash256::new(merkle::fixed_merkle_root(data, 0, None)), timestamp, nonce);
+        BlockchainBlock::from_parts(header, BlockBody::new(data))
+    }
+}
+
+impl<'a, T, H> BlockchainBlock<'a, T, H>
+where
+    T: FixedByteable + Byteable + Clone
+{
+    /// Check data is inside the block without copying the block data.
+    /// Same as `check_value_inblock` using the fixed-size hashing path.
+    ///
+    /// # Description
+    ///
+    /// * `data` - Data to be checked.
+    /// * `position` - Position of the data in the original array. 0 <= pos < block.data().len()
+    pub fn check_fixed_value_inblock(&self, data: &T, position: usize) -> bool {
+        if position >= self.body.data.len() { return false; }
+        self.header.merkle_root == merkle::fixed_merkle_root(&self.body.data, 0, Some((position, data)))
+    }
+}
+
+impl<'a, T: Clone + fmt::Debug, H: fmt::Debug> fmt::Debug for BlockchainBlock<'a, T, H>{
     fn fmt (&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter.debug_struct("Block")
             .field("Current Hash", &self.curr_hash)
-            .field("Previous Hash", &self.prev_hash)
-            .field("Data", &self.data)
-            .field("Timestamp", &self.timestamp)
-            .field("Timestamp", &self.timestamp)
-            .field("Nonce", &self.nonce)
-            .field("Merkleroot", &self.merkle_root)
-            .field("Version", &self.version)
+            .field("Previous Hash", &self.header.prev_hash)
+            .field("Height", &self.header.height)
+            .field("Data", &self.body.data)
+            .field("Timestamp", &self.header.timestamp)
+            .field("Nonce", &self.header.nonce)
+            .field("Merkleroot", &self.header.merkle_root)
+            .field("Version", &self.header.version)
+            .field("Target", &self.header.target)
+            .field("Chain", &self.header.chain_id)
+            .field("Extension", &self.header.extension)
             .finish()
     }    
 }
 
-
-impl<'a, T> Hashable for BlockchainBlock<'a, T>
-where
-    T: Byteable,
-{
-    fn calculate_hash (&mut self){
-        let prev_hash_bytes = &self.prev_hash;
-        let data_bytes = &self.data.bytes();
-        let timestamp_bytes = &self.timestamp.to_le_bytes();
-        let nonce_bytes = &self.nonce.to_le_bytes();        
-        let merkle_root_bytes = &self.merkle_root;
-        let version_bytes = &self.version.to_le_bytes();
-        let size =
-            match prev_hash_bytes { Some(prev_h) => prev_h.len(), None => 0 } +
-            data_bytes.len() +
-            timestamp_bytes.len() +
-            nonce_bytes.len() +
-            merkle_root_bytes.len() +
-            version_bytes.len();
-        let mut bytes : Vec<u8> = Vec::with_capacity(size);
-
-        match prev_hash_bytes {
-            Some(prev_h) => {
-                for idj in 0..prev_h.len(){
-                    bytes.push(prev_h[idj]);
-                }
-            },
-            None => ()
-        }
-        for idj in 0..data_bytes.len(){
-            bytes.push(data_bytes[idj]);
+/// Explorer-style summary of the block with hashes in lowercase hexadecimal.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 1] = [5];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// println!("{}", &block);
+/// assert_eq!(block.to_string(), "\
+/// Block b3475ee920e19444c51549b890c138893aa79a4a14e6db209f837fe467fe97d7
+///   prev:    none
+///   height:  0
+///   merkle:  a23a868b85672de9418b12652e4c1663cb38054c1206157fbf5826bf10f80b2d
+///   time:    4
+///   nonce:   3
+///   version: 3
+///   target:  ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+///   chain:   0
+///   items:   1");
+/// ```
+impl<'a, T: Clone, H> fmt::Display for BlockchainBlock<'a, T, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "Block {}", self.curr_hash)?;
+        match &self.header.prev_hash {
+            Some(prev_hash) => writeln!(formatter, "  prev:    {}", prev_hash)?,
+            None => writeln!(formatter, "  prev:    none")?,
         }
-        for idj in 0..timestamp_bytes.len(){
-            bytes.push(timestamp_bytes[idj]);
-        }
-        for idj in 0..nonce_bytes.len(){
-            bytes.push(nonce_bytes[idj]);
-        }
-        for idj in 0..merkle_root_bytes.len(){
-            bytes.push(merkle_root_bytes[idj]);
-        }
-        for idj in 0..version_bytes.len(){
-            bytes.push(version_bytes[idj]);
+        writeln!(formatter, "  height:  {}", self.header.height)?;
+        writeln!(formatter, "  merkle:  {}", self.header.merkle_root)?;
+        writeln!(formatter, "  time:    {}", self.header.timestamp)?;
+        writeln!(formatter, "  nonce:   {}", self.header.nonce)?;
+        writeln!(formatter, "  version: {}", self.header.version)?;
+        writeln!(formatter, "  target:  {}", hex::to_hex(&self.header.target))?;
+        writeln!(formatter, "  chain:   {}", self.header.chain_id)?;
+        write!(formatter, "  items:   {}", self.body.data.len())
+    }
+}
+
+
+impl<'a, T: Clone, H: Clone> Clone for BlockchainBlock<'a, T, H> {
+    fn clone(&self) -> BlockchainBlock<'a, T, H> {
+        BlockchainBlock {
+            curr_hash: self.curr_hash,
+            header: self.header.clone(),
+            body: self.body.clone(),
         }
+    }
+}
 
-        let digest = digest(Algorithm::SHA256, &bytes);
-        &self.curr_hash.copy_from_slice(&digest);
+/// Blocks are identified by their hash: two blocks are equal when their `curr_hash` are.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use std::collections::HashSet;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 1] = [5];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// let mut seen : HashSet<BlockchainBlock<i32>> = HashSet::new();
+/// assert!(seen.insert(block.clone()));
+/// assert!(!seen.insert(BlockchainBlock::new(None, &data, 4, 3)));
+/// assert!(seen.insert(BlockchainBlock::new(None, &data, 4, 4)));
+/// ```
+impl<'a, 'b, T: Clone, H> PartialEq<BlockchainBlock<'b, T, H>> for BlockchainBlock<'a, T, H> {
+    fn eq(&self, other: &BlockchainBlock<'b, T, H>) -> bool {
+        self.curr_hash == other.curr_hash
     }
+}
+
+impl<'a, T: Clone, H> Eq for BlockchainBlock<'a, T, H> {}
 
+impl<'a, T: Clone, H> std::hash::Hash for BlockchainBlock<'a, T, H> {
+    fn hash<S: std::hash::Hasher>(&self, state: &mut S) {
+        self.curr_hash.hash(state);
+    }
 }
 
-// #[test]
-// fn test() {
-  
-// }
+impl<'a, T, H> Hashable for BlockchainBlock<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable,
+{
+    fn calculate_hash (&mut self){
+        self.curr_hash = self.header.hash();
+    }
 
+}
@@ -0,0 +1,284 @@
+//! Length-prefixed binary encoding of a whole block.
+//!
+//! A block is encoded as:
+//!
+//! * `format` - 1 byte, revision of the encoding.
+//! * `version` - 1 byte.
+//! * `chain_id` - 4 bytes, little endian.
+//! * `flags` - 1 byte, bit 0 set when the block has a previous hash, bit 1 set
+//!   when the target is not `MAX_TARGET`, bit 2 set when the header extension has
+//!   a non-empty representation.
+//! * `prev_hash` - 32 bytes, only when bit 0 is set.
+//! * `height` - 8 bytes, little endian.
+//! * `target` - 32 bytes, only when bit 1 is set.
+//! * `merkle_root` - 32 bytes.
+//! * `timestamp` - 8 bytes, little endian.
+//! * `nonce` - 8 bytes, little endian.
+//! * `extension` - LEB128 varint length followed by the `Byteable::bytes` of the
+//!   extension, only when bit 2 is set.
+//! * item count - LEB128 varint.
+//! * every item - LEB128 varint length followed by the `Byteable::bytes` of the item.
+//!
+//! The block hash is not encoded: it is recalculated from the decoded header.
+
+use super::*;
+use crate::varint;
+
+/// Revision of the binary block format: 2 added the height, 3 the target, 4 the header
+/// extension, 5 the chain identifier.
+const FORMAT: u8 = 5;
+
+const FLAG_PREV: u8 = 0b0000_0001;
+const FLAG_TARGET: u8 = 0b0000_0010;
+const FLAG_EXTENSION: u8 = 0b0000_0100;
+const KNOWN_FLAGS: u8 = FLAG_PREV | FLAG_TARGET | FLAG_EXTENSION;
+
+impl<'a, T, H> BlockchainBlock<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable
+{
+    /// Encode the block in the binary wire format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data = [String::from("first"), String::from("second")];
+    /// let block : BlockchainBlock<String> = BlockchainBlock::new(Some(Hash256::new([1; BLOCKHASHLEN])), &data, 4, 3);
+    /// let bytes = block.to_bytes();
+    /// let decoded : OwnedBlock<String> = BlockchainBlock::from_bytes(&bytes).unwrap();
+    /// assert_eq!(decoded, block);
+    /// assert_eq!(decoded.data(), &data);
+    /// assert_eq!(BlockchainBlock::<String>::from_bytes(&bytes[..bytes.len() - 1]), Err(BlockError::Truncated));
+    ///
+    /// let mut future = bytes.clone();
+    /// future[0] += 1;
+    /// assert_eq!(BlockchainBlock::<String>::from_bytes(&future), Err(BlockError::UnsupportedFormat(future[0])));
+    ///
+    /// let extended : BlockchainBlock<String, String> = BlockchainBlock::builder().extension(String::from("epoch 7")).data(&data).build();
+    /// let decoded : OwnedBlock<String, String> = BlockchainBlock::from_bytes(&extended.to_bytes()).unwrap();
+    /// assert_eq!(decoded.extension(), "epoch 7");
+    /// assert_eq!(BlockchainBlock::<String>::from_bytes(&extended.to_bytes()), Err(BlockError::Malformed));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = self.header();
+        let extension = header.extension.bytes();
+        let mut out : Vec<u8> = Vec::with_capacity(self.encoded_size());
+        out.push(FORMAT);
+        out.push(header.version);
+        out.extend_from_slice(&header.chain_id.to_le_bytes());
+        let mut flags = 0;
+        if header.prev_hash.is_some() { flags |= FLAG_PREV; }
+        if header.target != MAX_TARGET { flags |= FLAG_TARGET; }
+        if !extension.is_empty() { flags |= FLAG_EXTENSION; }
+        out.push(flags);
+        if let Some(prev_hash) = &header.prev_hash { out.extend_from_slice(prev_hash.as_bytes()); }
+        out.extend_from_slice(&header.height.to_le_bytes());
+        if flags & FLAG_TARGET != 0 { out.extend_from_slice(&header.target); }
+        out.extend_from_slice(header.merkle_root.as_bytes());
+        out.extend_from_slice(&header.timestamp.to_le_bytes());
+        out.extend_from_slice(&header.nonce.to_le_bytes());
+        if flags & FLAG_EXTENSION != 0 {
+            varint::write_u64(&mut out, extension.len() as u64);
+            out.extend_from_slice(&extension);
+        }
+        varint::write_u64(&mut out, self.data().len() as u64);
+        for item in self.data() {
+            let bytes = item.bytes();
+            varint::write_u64(&mut out, bytes.len() as u64);
+            out.extend_from_slice(&bytes);
+        }
+        out
+    }
+
+    /// Size in bytes of the block encoded by [`to_bytes`](BlockchainBlock::to_bytes).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 2] = [5, 6];
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+    /// assert_eq!(block.encoded_size(), block.to_bytes().len());
+    /// ```
+    pub fn encoded_size(&self) -> usize {
+        let prev_hash_len = if self.header().prev_hash.is_some() { BLOCKHASHLEN } else { 0 };
+        let target_len = if self.header().target != MAX_TARGET { BLOCKHASHLEN } else { 0 };
+        let extension_len = match self.header().extension.bytes().len() {
+            0 => 0,
+            len => varint::len_u64(len as u64) + len,
+        };
+        1 + 1 + 4 + 1 + prev_hash_len + 8 + target_len + BLOCKHASHLEN + 8 + 8 + extension_len + self.weight()
+    }
+
+    /// Weight of the data: the size in bytes of the encoded items and their count,
+    /// that is the encoded size of the block without its header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data = [String::from("first"), String::from("second")];
+    /// let block : BlockchainBlock<String> = BlockchainBlock::new(None, &data, 4, 3);
+    /// assert_eq!(block.weight(), 1 + (1 + 5) + (1 + 6));
+    /// assert_eq!(block.weight(), 1 + data.iter().map(BlockPacker::item_weight).sum::<usize>());
+    /// ```
+    pub fn weight(&self) -> usize {
+        varint::len_u64(self.data().len() as u64) + self.iter().map(item_weight).sum::<usize>()
+    }
+
+    /// Check the encoded block fits in `max_block_bytes`.
+    /// Fails with `BlockError::TooLarge` otherwise.
+    pub fn check_size(&self, max_block_bytes: usize) -> Result<(), BlockError> {
+        let size = self.encoded_size();
+        if size > max_block_bytes { return Err(BlockError::TooLarge(size)); }
+        Ok(())
+    }
+}
+
+impl<T, H> BlockchainBlock<'static, T, H>
+where
+    T: FromBytes + Byteable + Clone,
+    H: FromBytes + Byteable
+{
+    /// Decode a block encoded by [`to_bytes`](BlockchainBlock::to_bytes).
+    ///
+    /// Fails when the input is truncated or malformed, when bytes remain after the block,
+    /// when an item cannot be decoded or when the data does not match the merkle root.
+    /// Inputs of another format revision are rejected with `BlockError::UnsupportedFormat`.
+    /// Inputs larger than [`DEFAULT_MAX_BLOCK_BYTES`] are rejected with `BlockError::TooLarge`,
+    /// see [`from_bytes_limited`](BlockchainBlock::from_bytes_limited) for other limits.
+    pub fn from_bytes(bytes: &[u8]) -> Result<OwnedBlock<T, H>, BlockError> {
+        BlockchainBlock::from_bytes_limited(bytes, DEFAULT_MAX_BLOCK_BYTES)
+    }
+
+    /// Decode a block as [`from_bytes`](BlockchainBlock::from_bytes) does, failing with
+    /// `BlockError::TooLarge` before decoding anything when the input is larger than
+    /// `max_block_bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let params = ChainParams::default();
+    /// let data = vec![String::from("x"); 100];
+    /// let block : BlockchainBlock<String> = BlockchainBlock::new(None, &data, 4, 3);
+    /// let bytes = block.to_bytes();
+    /// assert!(BlockchainBlock::<String>::from_bytes_limited(&bytes, params.max_block_bytes).is_ok());
+    /// assert_eq!(BlockchainBlock::<String>::from_bytes_limited(&bytes, 100), Err(BlockError::TooLarge(bytes.len())));
+    ///
+    /// let large = vec![String::from("x"); DEFAULT_MAX_BLOCK_BYTES / 2];
+    /// let bytes = BlockchainBlock::<String>::new(None, &large, 4, 3).to_bytes();
+    /// assert_eq!(BlockchainBlock::<String>::from_bytes(&bytes), Err(BlockError::TooLarge(bytes.len())));
+    /// assert!(BlockchainBlock::<String>::from_bytes_limited(&bytes, bytes.len()).is_ok());
+    /// ```
+    pub fn from_bytes_limited(bytes: &[u8], max_block_bytes: usize) -> Result<OwnedBlock<T, H>, BlockError> {
+        if bytes.len() > max_block_bytes { return Err(BlockError::TooLarge(bytes.len())); }
+        let (header, mut pos) = read_header(bytes)?;
+        let count = read_varint(bytes, &mut pos)?;
+
+        // every item takes at least one byte, so this bounds the allocation
+        if count > (bytes.len() - pos) as u64 { return Err(BlockError::Truncated); }
+        let mut data : Vec<T> = Vec::with_capacity(count as usize);
+        for position in 0..count as usize {
+            let len = read_varint(bytes, &mut pos)?;
+            if len > (bytes.len() - pos) as u64 { return Err(BlockError::Truncated); }
+            let end = pos + len as usize;
+            let item = T::from_bytes(&bytes[pos..end]).ok_or(BlockError::InvalidItem(position))?;
+            data.push(item);
+            pos = end;
+        }
+        if pos != bytes.len() { return Err(BlockError::TrailingBytes); }
+
+        let body = BlockBody::owned(data);
+        if !body.matches(&header) { return Err(BlockError::MerkleMismatch); }
+        Ok(BlockchainBlock::from_parts(header, body))
+    }
+}
+
+/// Decode the header at the start of an encoded block, returning it with the position
+/// of the item count, so the header can be checked before the data is decoded.
+pub(crate) fn read_header<H: FromBytes>(bytes: &[u8]) -> Result<(BlockHeader<H>, usize), BlockError> {
+    let mut pos = 0;
+    let format = read_byte(bytes, &mut pos)?;
+    if format != FORMAT { return Err(BlockError::UnsupportedFormat(format)); }
+    let version = read_byte(bytes, &mut pos)?;
+    let chain_id = read_u32_le(bytes, &mut pos)?;
+    let flags = read_byte(bytes, &mut pos)?;
+    if flags & !KNOWN_FLAGS != 0 { return Err(BlockError::Malformed); }
+    let prev_hash = if flags & FLAG_PREV != 0 { Some(Hash256::new(read_hash(bytes, &mut pos)?)) } else { None };
+    let height = read_u64_le(bytes, &mut pos)?;
+    let target = if flags & FLAG_TARGET != 0 {
+        let target = read_hash(bytes, &mut pos)?;
+        if target == MAX_TARGET { return Err(BlockError::Malformed); }
+        target
+    } else { MAX_TARGET };
+    let merkle_root = Hash256::new(read_hash(bytes, &mut pos)?);
+    let timestamp = read_u64_le(bytes, &mut pos)?;
+    let nonce = read_u64_le(bytes, &mut pos)?;
+    let extension_bytes : &[u8] = if flags & FLAG_EXTENSION != 0 {
+        let len = read_varint(bytes, &mut pos)?;
+        if len == 0 { return Err(BlockError::Malformed); }
+        if len > (bytes.len() - pos) as u64 { return Err(BlockError::Truncated); }
+        let end = pos + len as usize;
+        let extension_bytes = &bytes[pos..end];
+        pos = end;
+        extension_bytes
+    } else { &[] };
+    let extension = H::from_bytes(extension_bytes).ok_or(BlockError::Malformed)?;
+    Ok((BlockHeader { prev_hash, height, merkle_root, timestamp, nonce, version, target, chain_id, extension }, pos))
+}
+
+/// Size of `item` in the encoded block: its length prefix and its bytes.
+pub(crate) fn item_weight<T: Byteable>(item: &T) -> usize {
+    let len = item.bytes().len();
+    varint::len_u64(len as u64) + len
+}
+
+fn read_byte(bytes: &[u8], pos: &mut usize) -> Result<u8, BlockError> {
+    let byte = *bytes.get(*pos).ok_or(BlockError::Truncated)?;
+    *pos += 1;
+    Ok(byte)
+}
+
+fn read_hash(bytes: &[u8], pos: &mut usize) -> Result<BlockHash, BlockError> {
+    let slice = bytes.get(*pos..*pos + BLOCKHASHLEN).ok_or(BlockError::Truncated)?;
+    let mut hash : BlockHash = [0; BLOCKHASHLEN];
+    hash.copy_from_slice(slice);
+    *pos += BLOCKHASHLEN;
+    Ok(hash)
+}
+
+fn read_u32_le(bytes: &[u8], pos: &mut usize) -> Result<u32, BlockError> {
+    let slice = bytes.get(*pos..*pos + 4).ok_or(BlockError::Truncated)?;
+    let mut value = [0u8; 4];
+    value.copy_from_slice(slice);
+    *pos += 4;
+    Ok(u32::from_le_bytes(value))
+}
+
+fn read_u64_le(bytes: &[u8], pos: &mut usize) -> Result<u64, BlockError> {
+    let slice = bytes.get(*pos..*pos + 8).ok_or(BlockError::Truncated)?;
+    let mut value = [0u8; 8];
+    value.copy_from_slice(slice);
+    *pos += 8;
+    Ok(u64::from_le_bytes(value))
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, BlockError> {
+    let start = *pos;
+    varint::read_u64(bytes, pos).ok_or_else(|| {
+        // running out of input with the continuation bit still set is a truncation
+        let unterminated = *pos >= bytes.len() && bytes[start..].iter().all(|b| b & 0x80 != 0);
+        if unterminated { BlockError::Truncated } else { BlockError::Malformed }
+    })
+}
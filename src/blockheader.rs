@@ -0,0 +1,179 @@
+use super::*;
+
+/// Header of a block: every field needed to hash and link a block without its data.
+///
+/// The data is committed to through `merkle_root`, so headers can be transmitted and
+/// validated independently of the payloads.
+///
+/// `extension` carries additional consensus data and is hashed after the other fields.
+/// The default `()` extension adds nothing to the hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlockHeader<H = ()> {
+    /// hash of the previous block. Is `None` for the first block
+    pub prev_hash: Option<Hash256>,
+    /// number of blocks before this one in the chain. Is `0` for the first block
+    pub height: u64,
+    /// root of a sha256 hash tree where the leaves are transactions
+    pub merkle_root: Hash256,
+    /// time of block creation in seconds since 1970-01-01T00:00 UTC
+    pub timestamp: u64,
+    /// field used for giving variability
+    pub nonce: u64,
+    /// version of the protocol used to create the block
+    pub version: u8,
+    /// difficulty target as a 256-bit big-endian integer, the block hash must not exceed it
+    pub target: BlockHash,
+    /// identifier of the chain the block belongs to
+    pub chain_id: u32,
+    /// additional consensus data
+    pub extension: H,
+}
+
+impl BlockHeader {
+    /// Constructs a new `BlockHeader` at height `0` of chain `0` for the current protocol
+    /// `VERSION` with the easiest target, `MAX_TARGET`.
+    ///
+    /// # Description
+    ///
+    /// * `prev_hash` - Hash of the previous block.
+    /// * `merkle_root` - Merkle root of the block data.
+    /// * `timestamp` - Creation time in *Unix time* format.
+    /// * `nonce` - Nonce to include variability in the hash calculation.
+    pub fn new(prev_hash: Option<Hash256>, merkle_root: Hash256, timestamp: u64, nonce: u64) -> BlockHeader {
+        BlockHeader { prev_hash, height: 0, merkle_root, timestamp, nonce, version: VERSION, target: MAX_TARGET, chain_id: 0, extension: () }
+    }
+}
+
+impl<H: Byteable> BlockHeader<H> {
+    /// Replace the extension of the header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let header = BlockHeader::new(None, Hash256::ZERO, 4, 3);
+    /// let extended = header.with_extension(String::from("epoch 7"));
+    /// assert_eq!(extended.extension, "epoch 7");
+    /// assert_ne!(extended.hash(), header.hash());
+    /// ```
+    pub fn with_extension<E: Byteable>(self, extension: E) -> BlockHeader<E> {
+        BlockHeader {
+            prev_hash: self.prev_hash,
+            height: self.height,
+            merkle_root: self.merkle_root,
+            timestamp: self.timestamp,
+            nonce: self.nonce,
+            version: self.version,
+            target: self.target,
+            chain_id: self.chain_id,
+            extension,
+        }
+    }
+
+    /// Calculate the hash of the block from the header fields only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 1] = [5];
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+    /// assert_eq!(block.header().hash(), block.curr_hash());
+    /// ```
+    pub fn hash(&self) -> Hash256 {
+        Hash256::new(sha256(&self.bytes()))
+    }
+
+    /// Check the hash of the header does not exceed the difficulty target,
+    /// both read as 256-bit big-endian integers.
+    pub fn meets_target(&self) -> bool {
+        self.hash().as_bytes() <= &self.target
+    }
+
+    /// Work needed to meet the difficulty target of the header.
+    pub fn work(&self) -> Work {
+        Work::from_target(&self.target)
+    }
+
+    /// Check the header commits to no data, its merkle root being `EMPTY_MERKLE_ROOT`.
+    pub fn is_empty(&self) -> bool {
+        self.merkle_root == EMPTY_MERKLE_ROOT
+    }
+
+    /// Check this header is the first one of a chain.
+    pub fn is_genesis(&self) -> bool {
+        self.prev_hash.is_none()
+    }
+
+    /// Check this header directly follows `parent`: it links to the hash of `parent`,
+    /// its height is the next one and both belong to the same chain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 1] = [5];
+    /// let parent : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+    /// let child : BlockchainBlock<i32> = BlockchainBlock::builder().parent(parent.header()).timestamp(5).data(&data).build();
+    /// assert_eq!(child.height(), 1);
+    /// assert!(child.header().follows(parent.header()));
+    /// assert!(!parent.header().follows(child.header()));
+    ///
+    /// let orphan : BlockchainBlock<i32> = BlockchainBlock::new(Some(parent.curr_hash()), &data, 5, 3);
+    /// assert!(!orphan.header().follows(parent.header()));
+    /// ```
+    pub fn follows(&self, parent: &BlockHeader<H>) -> bool {
+        self.prev_hash == Some(parent.hash()) &&
+            parent.height.checked_add(1) == Some(self.height) &&
+            self.chain_id == parent.chain_id
+    }
+
+    /// Position of the nonce in the bytes hashed for the header.
+    pub(crate) fn nonce_offset(&self) -> usize {
+        match self.prev_hash { Some(_) => BLOCKHASHLEN + 8, None => 8 }
+    }
+}
+
+impl<H: Byteable> Byteable for BlockHeader<H> {
+    fn bytes(&self) -> Vec<u8> {
+        let prev_hash_bytes = &self.prev_hash;
+        let timestamp_bytes = &self.timestamp.to_le_bytes();
+        let nonce_bytes = &self.nonce.to_le_bytes();
+        let merkle_root_bytes = self.merkle_root.as_bytes();
+        let version_bytes = &self.version.to_le_bytes();
+        let height_bytes = &self.height.to_le_bytes();
+        let target_bytes = &self.target;
+        let chain_id_bytes = &self.chain_id.to_le_bytes();
+        let extension_bytes = &self.extension.bytes();
+        let size =
+            match prev_hash_bytes { Some(prev_h) => prev_h.as_bytes().len(), None => 0 } +
+            timestamp_bytes.len() +
+            nonce_bytes.len() +
+            merkle_root_bytes.len() +
+            version_bytes.len() +
+            height_bytes.len() +
+            target_bytes.len() +
+            chain_id_bytes.len() +
+            extension_bytes.len();
+        let mut bytes : Vec<u8> = Vec::with_capacity(size);
+
+        if let Some(prev_h) = prev_hash_bytes {
+            bytes.extend_from_slice(prev_h.as_bytes());
+        }
+        bytes.extend_from_slice(timestamp_bytes);
+        bytes.extend_from_slice(nonce_bytes);
+        bytes.extend_from_slice(merkle_root_bytes);
+        bytes.extend_from_slice(version_bytes);
+        bytes.extend_from_slice(height_bytes);
+        bytes.extend_from_slice(target_bytes);
+        bytes.extend_from_slice(chain_id_bytes);
+        bytes.extend_from_slice(extension_bytes);
+        bytes
+    }
+}
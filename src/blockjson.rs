@@ -0,0 +1,194 @@
+//! JSON export and import of a whole block.
+//!
+//! A block is exported as a single JSON object with the members, in this order:
+//!
+//! * `hash` - block hash, 64 lowercase hexadecimal digits.
+//! * `version` - header version, number.
+//! * `chain_id` - chain identifier, number.
+//! * `prev_hash` - previous block hash in hexadecimal, `null` for a first block.
+//! * `height` - number.
+//! * `merkle_root` - merkle root of the data in hexadecimal.
+//! * `timestamp` - number.
+//! * `nonce` - number.
+//! * `target` - difficulty target in hexadecimal.
+//! * `extension` - base64 of the `Byteable::bytes` of the header extension,
+//!   `""` for a block without extension.
+//! * `items` - array with the base64 of the `Byteable::bytes` of each item.
+//!
+//! Numbers are written as integers and read back exactly, even above `2^53`.
+//! The base64 uses the standard alphabet with padding (RFC 4648). The export contains
+//! no whitespace, so the same block always produces the same text.
+
+use super::*;
+use crate::{base64, hex, json};
+use crate::json::Value;
+
+impl<'a, T, H> BlockchainBlock<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable
+{
+    /// Export the block as JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data = [String::from("first"), String::from("second")];
+    /// let block : BlockchainBlock<String> = BlockchainBlock::new(None, &data, 4, u64::MAX);
+    /// let text = block.to_json();
+    /// assert!(text.starts_with(&format!("{{\"hash\":\"{}\",\"version\":3,", block.curr_hash().to_hex())));
+    /// assert!(text.contains("\"nonce\":18446744073709551615,"));
+    /// assert!(text.ends_with("\"extension\":\"\",\"items\":[\"Zmlyc3Q=\",\"c2Vjb25k\"]}"));
+    ///
+    /// let imported : OwnedBlock<String> = BlockchainBlock::from_json(&text).unwrap();
+    /// assert_eq!(imported, block);
+    /// assert_eq!(imported.nonce(), u64::MAX);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let header = self.header();
+        let mut out = String::new();
+        out.push_str("{\"hash\":");
+        json::write_string(&mut out, &self.curr_hash().to_hex());
+        out.push_str(&format!(",\"version\":{},\"chain_id\":{},\"prev_hash\":", header.version, header.chain_id));
+        match &header.prev_hash {
+            Some(prev_hash) => json::write_string(&mut out, &prev_hash.to_hex()),
+            None => out.push_str("null"),
+        }
+        out.push_str(&format!(",\"height\":{},\"merkle_root\":", header.height));
+        json::write_string(&mut out, &header.merkle_root.to_hex());
+        out.push_str(&format!(",\"timestamp\":{},\"nonce\":{},\"target\":", header.timestamp, header.nonce));
+        json::write_string(&mut out, &hex::to_hex(&header.target));
+        out.push_str(",\"extension\":");
+        json::write_string(&mut out, &base64::encode(&header.extension.bytes()));
+        out.push_str(",\"items\":[");
+        for (index, item) in self.iter().enumerate() {
+            if index > 0 { out.push(','); }
+            json::write_string(&mut out, &base64::encode(&item.bytes()));
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+impl<T, H> BlockchainBlock<'static, T, H>
+where
+    T: FromBytes + Byteable + Clone,
+    H: FromBytes + Byteable
+{
+    /// Import a block exported by [`to_json`](BlockchainBlock::to_json).
+    ///
+    /// # Description
+    ///
+    /// * `text` - JSON text. Members may come in any order and with whitespace,
+    ///   but all of them must be present and no other is accepted.
+    ///
+    /// Fails with `BlockError::InvalidJson` when the text is not JSON, with
+    /// `BlockError::Malformed` when it does not follow the schema, with
+    /// `BlockError::InvalidItem` when an item cannot be decoded, and with
+    /// `BlockError::MerkleMismatch` or `BlockError::HashMismatch` when the data or
+    /// the `hash` member do not match the header. Texts longer than
+    /// [`DEFAULT_MAX_BLOCK_BYTES`] are rejected with `BlockError::TooLarge`, see
+    /// [`from_json_limited`](BlockchainBlock::from_json_limited) for other limits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 2] = [5, 6];
+    /// let block : BlockchainBlock<i32> = BlockchainBlock::new(Some(Hash256::new([1; BLOCKHASHLEN])), &data, 4, 3);
+    /// let text = block.to_json();
+    /// assert_eq!(BlockchainBlock::<i32>::from_json(&text.replace("\"nonce\":3", "\"nonce\":4")), Err(BlockError::HashMismatch));
+    /// assert_eq!(BlockchainBlock::<i32>::from_json(&text.replace("BQAAAA==", "BwAAAA==")), Err(BlockError::MerkleMismatch));
+    /// assert_eq!(BlockchainBlock::<i32>::from_json(&text.replace("BQAAAA==", "BQAA")), Err(BlockError::InvalidItem(0)));
+    /// assert_eq!(BlockchainBlock::<i32>::from_json(&text.replace("\"nonce\"", "\"Nonce\"")), Err(BlockError::Malformed));
+    /// assert!(matches!(BlockchainBlock::<i32>::from_json(&text[1..]), Err(BlockError::InvalidJson(_))));
+    /// ```
+    pub fn from_json(text: &str) -> Result<OwnedBlock<T, H>, BlockError> {
+        BlockchainBlock::from_json_limited(text, DEFAULT_MAX_BLOCK_BYTES)
+    }
+
+    /// Import a block as [`from_json`](BlockchainBlock::from_json) does, failing with
+    /// `BlockError::TooLarge` before parsing anything when the text is longer than
+    /// `max_len` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data = vec![String::from("x"); 100];
+    /// let text = BlockchainBlock::<String>::new(None, &data, 4, 3).to_json();
+    /// assert!(BlockchainBlock::<String>::from_json_limited(&text, text.len()).is_ok());
+    /// assert_eq!(BlockchainBlock::<String>::from_json_limited(&text, 100), Err(BlockError::TooLarge(text.len())));
+    ///
+    /// let padded = format!("{}{}", " ".repeat(DEFAULT_MAX_BLOCK_BYTES), text);
+    /// assert_eq!(BlockchainBlock::<String>::from_json(&padded), Err(BlockError::TooLarge(padded.len())));
+    /// ```
+    pub fn from_json_limited(text: &str, max_len: usize) -> Result<OwnedBlock<T, H>, BlockError> {
+        if text.len() > max_len { return Err(BlockError::TooLarge(text.len())); }
+        let members = match json::parse(text).map_err(BlockError::InvalidJson)? {
+            Value::Object(members) => members,
+            _ => return Err(BlockError::Malformed),
+        };
+        const NAMES: [&str; 11] = ["hash", "version", "chain_id", "prev_hash", "height", "merkle_root",
+                                   "timestamp", "nonce", "target", "extension", "items"];
+        if members.iter().any(|(key, _)| !NAMES.contains(&key.as_str())) { return Err(BlockError::Malformed); }
+        let member = |name: &str| -> Result<&Value, BlockError> {
+            members.iter().find(|(key, _)| key == name).map(|(_, value)| value).ok_or(BlockError::Malformed)
+        };
+
+        let hash = read_hash(member("hash")?)?;
+        let version = read_integer(member("version")?)?;
+        let chain_id = read_integer(member("chain_id")?)?;
+        let prev_hash = match member("prev_hash")? {
+            Value::Null => None,
+            value => Some(read_hash(value)?),
+        };
+        let height = read_integer(member("height")?)?;
+        let merkle_root = read_hash(member("merkle_root")?)?;
+        let timestamp = read_integer(member("timestamp")?)?;
+        let nonce = read_integer(member("nonce")?)?;
+        let target = read_hash(member("target")?)?.into();
+        let extension = H::from_bytes(&read_base64(member("extension")?)?).ok_or(BlockError::Malformed)?;
+        let data = match member("items")? {
+            Value::Array(items) => items.iter().enumerate()
+                .map(|(position, item)| T::from_bytes(&read_base64(item)?).ok_or(BlockError::InvalidItem(position)))
+                .collect::<Result<Vec<T>, BlockError>>()?,
+            _ => return Err(BlockError::Malformed),
+        };
+
+        let header = BlockHeader { prev_hash, height, merkle_root, timestamp, nonce, version, target, chain_id, extension };
+        let body = BlockBody::owned(data);
+        if !body.matches(&header) { return Err(BlockError::MerkleMismatch); }
+        if header.hash() != hash { return Err(BlockError::HashMismatch); }
+        Ok(BlockchainBlock::from_parts(header, body))
+    }
+}
+
+fn read_integer<N: std::str::FromStr>(value: &Value) -> Result<N, BlockError> {
+    match value {
+        // rejects fractions, exponents, signs and values out of range of `N`
+        Value::Number(text) if text.chars().all(|c| c.is_ascii_digit()) => text.parse().map_err(|_| BlockError::Malformed),
+        _ => Err(BlockError::Malformed),
+    }
+}
+
+fn read_hash(value: &Value) -> Result<Hash256, BlockError> {
+    match value {
+        Value::String(text) => text.parse().map_err(|_| BlockError::Malformed),
+        _ => Err(BlockError::Malformed),
+    }
+}
+
+fn read_base64(value: &Value) -> Result<Vec<u8>, BlockError> {
+    match value {
+        Value::String(text) => base64::decode(text).ok_or(BlockError::Malformed),
+        _ => Err(BlockError::Malformed),
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+use crate::{blockencoding, varint};
+
+/// Fill a block with items up to a weight limit.
+///
+/// Items are offered in priority order, highest first. An item is taken when it still
+/// fits, otherwise it is skipped and smaller items after it may still be taken. The
+/// weight counted is the one of [`BlockchainBlock::weight`], leave room for the header
+/// when the limit comes from `max_block_bytes`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let offers = vec![String::from("aaaaaaaaaa"), String::from("bbbbbbbbbbbbbbbbbbbb"), String::from("cc")];
+/// let mut packer : BlockPacker<String> = BlockPacker::new(16);
+/// assert_eq!(packer.fill(offers), 2);
+/// assert_eq!(packer.items(), &[String::from("aaaaaaaaaa"), String::from("cc")]);
+/// assert_eq!(packer.weight(), 15);
+///
+/// let block : BlockchainBlock<String> = BlockchainBlock::builder().data(packer.items()).build();
+/// assert_eq!(block.weight(), packer.weight());
+/// ```
+pub struct BlockPacker<T> {
+    max_weight: usize,
+    items_weight: usize,
+    items: Vec<T>,
+}
+
+impl<T: Byteable> BlockPacker<T> {
+    /// Constructs a new empty `BlockPacker<T>` accepting up to `max_weight`.
+    pub fn new(max_weight: usize) -> BlockPacker<T> {
+        BlockPacker { max_weight, items_weight: 0, items: Vec::new() }
+    }
+
+    /// Weight `item` adds to a block, not counting the growth of the item count.
+    pub fn item_weight(item: &T) -> usize {
+        blockencoding::item_weight(item)
+    }
+
+    /// Weight of the items taken so far, counted as in [`BlockchainBlock::weight`].
+    pub fn weight(&self) -> usize {
+        varint::len_u64(self.items.len() as u64) + self.items_weight
+    }
+
+    /// Weight still available.
+    pub fn remaining(&self) -> usize {
+        self.max_weight.saturating_sub(self.weight())
+    }
+
+    /// Take `item` when it fits, returning whether it was taken.
+    pub fn push(&mut self, item: T) -> bool {
+        let item_weight = BlockPacker::item_weight(&item);
+        let weight = varint::len_u64(self.items.len() as u64 + 1) + self.items_weight + item_weight;
+        if weight > self.max_weight { return false; }
+        self.items_weight += item_weight;
+        self.items.push(item);
+        true
+    }
+
+    /// Offer every item of `items`, returning the number of items taken.
+    pub fn fill<I: IntoIterator<Item = T>>(&mut self, items: I) -> usize {
+        items.into_iter().filter_map(|item| if self.push(item) { Some(()) } else { None }).count()
+    }
+
+    /// Items taken, in the order they were offered.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Take the items out of the packer.
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+
+/// Block being mined: only the nonce changes between hash attempts.
+///
+/// # Description
+///
+/// The header is serialized once and each attempt only overwrites the nonce bytes
+/// before hashing, so neither the data, the merkle root nor the header extension are
+/// touched during the search. The nonce lies in the first 64-byte block of the SHA-256
+/// input, so there is no midstate to reuse and every attempt hashes the whole header.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let mut target : BlockHash = MAX_TARGET;
+/// target[0] = 0x0f;
+/// let data = vec![String::from("payload"); 1000];
+/// let block : BlockchainBlock<String> = BlockchainBlock::builder().target(target).data(&data).build();
+///
+/// let mut template = BlockTemplate::new(block);
+/// let nonce = template.search(0..).unwrap();
+/// assert_eq!(template.nonce(), nonce);
+/// let mined = template.into_block();
+/// assert_eq!(mined.nonce(), nonce);
+/// assert!(mined.meets_target());
+/// assert_eq!(mined.verify(), Ok(()));
+/// ```
+pub struct BlockTemplate<'a, T: Clone, H = ()> {
+    block: BlockchainBlock<'a, T, H>,
+    preimage: Vec<u8>,
+    nonce_offset: usize,
+}
+
+impl<'a, T, H> BlockTemplate<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable
+{
+    /// Constructs a new `BlockTemplate` starting from the header and data of `block`.
+    pub fn new(block: BlockchainBlock<'a, T, H>) -> BlockTemplate<'a, T, H> {
+        let preimage = block.header().bytes();
+        let nonce_offset = block.header().nonce_offset();
+        BlockTemplate { block, preimage, nonce_offset }
+    }
+
+    /// Return the current nonce.
+    pub fn nonce(&self) -> u64 {
+        let mut nonce = [0u8; 8];
+        nonce.copy_from_slice(&self.preimage[self.nonce_offset..self.nonce_offset + 8]);
+        u64::from_le_bytes(nonce)
+    }
+
+    /// Set the nonce of the next attempt.
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.preimage[self.nonce_offset..self.nonce_offset + 8].copy_from_slice(&nonce.to_le_bytes());
+    }
+
+    /// Calculate the hash of the block with the current nonce.
+    pub fn hash(&self) -> Hash256 {
+        Hash256::new(sha256(&self.preimage))
+    }
+
+    /// Check the hash with the current nonce does not exceed the difficulty target.
+    pub fn meets_target(&self) -> bool {
+        self.hash().as_bytes() <= &self.block.header().target
+    }
+
+    /// Try the nonces of `nonces` in order and stop at the first meeting the target,
+    /// returning it. The template keeps the last nonce tried.
+    pub fn search<I: IntoIterator<Item = u64>>(&mut self, nonces: I) -> Option<u64> {
+        for nonce in nonces {
+            self.set_nonce(nonce);
+            if self.meets_target() { return Some(nonce); }
+        }
+        None
+    }
+
+    /// Return the block with the current nonce.
+    pub fn into_block(self) -> BlockchainBlock<'a, T, H> {
+        let nonce = self.nonce();
+        let mut block = self.block;
+        block.set_nonce(nonce);
+        block
+    }
+}
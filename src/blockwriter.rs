@@ -0,0 +1,165 @@
+use std::marker::PhantomData;
+use super::*;
+use crate::merkle;
+
+/// Node of the merkle tree being computed.
+struct Frame {
+    /// number of items below the node
+    size: u64,
+    /// root of the left half, once complete
+    left: Option<BlockHash>,
+}
+
+/// Streaming construction of a block header for data too large to hold in memory.
+///
+/// Items are written one at a time and only the merkle tree nodes pending completion
+/// are kept, `O(log n)` hashes, so the data never needs to be materialized. The shape of
+/// the merkle tree depends on the number of items, which must be given up front.
+///
+/// The resulting header is the one of a `BlockchainBlock` built from the same items.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let template = BlockHeader::new(None, EMPTY_MERKLE_ROOT, 4, 3);
+/// let mut writer : BlockWriter<i32> = BlockWriter::new(template, 1000);
+/// writer.write_all(0..1000).unwrap();
+/// let header = writer.finish().unwrap();
+///
+/// let data : Vec<i32> = (0..1000).collect();
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// assert_eq!(&header, block.header());
+/// assert_eq!(header.hash(), block.curr_hash());
+///
+/// let mut short : BlockWriter<i32> = BlockWriter::new(template, 2);
+/// short.write(5).unwrap();
+/// assert_eq!(short.finish(), Err(BlockError::ItemCountMismatch(1)));
+/// ```
+pub struct BlockWriter<T, H = ()> {
+    header: BlockHeader<H>,
+    count: u64,
+    written: u64,
+    stack: Vec<Frame>,
+    /// bytes of the first item of a pending pair of leaves
+    pending: Option<Vec<u8>>,
+    root: Option<BlockHash>,
+    items: PhantomData<T>,
+}
+
+impl<T, H> BlockWriter<T, H>
+where
+    T: Byteable,
+    H: Byteable
+{
+    /// Constructs a new `BlockWriter<T>` expecting `count` items.
+    ///
+    /// # Description
+    ///
+    /// * `header` - Header fields of the block; the merkle root is replaced once every item is written.
+    /// * `count` - Number of items that will be written.
+    pub fn new(header: BlockHeader<H>, count: u64) -> BlockWriter<T, H> {
+        let mut writer = BlockWriter {
+            header,
+            count,
+            written: 0,
+            stack: Vec::new(),
+            pending: None,
+            root: None,
+            items: PhantomData,
+        };
+        if count == 0 {
+            writer.root = Some(*EMPTY_MERKLE_ROOT.as_bytes());
+        } else {
+            writer.stack.push(Frame { size: count, left: None });
+            writer.descend();
+        }
+        writer
+    }
+
+    /// Number of items written so far.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Add the next item to the merkle tree.
+    /// Fails with `BlockError::ItemCountMismatch` when every expected item was already written.
+    pub fn write(&mut self, item: T) -> Result<(), BlockError> {
+        if self.written == self.count { return Err(BlockError::ItemCountMismatch(self.written + 1)); }
+        self.written += 1;
+        let bytes = item.bytes();
+        let leaves = self.stack.last().map(|frame| frame.size).unwrap_or(0);
+        if leaves == 1 {
+            let mut doubled = bytes.clone();
+            doubled.extend_from_slice(&bytes);
+            self.complete(sha256(&doubled));
+        } else {
+            match self.pending.take() {
+                None => self.pending = Some(bytes),
+                Some(mut pair) => {
+                    pair.extend_from_slice(&bytes);
+                    self.complete(sha256(&pair));
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Add every item of `items` to the merkle tree.
+    pub fn write_all<I: IntoIterator<Item = T>>(&mut self, items: I) -> Result<(), BlockError> {
+        for item in items {
+            self.write(item)?;
+        }
+        Ok(())
+    }
+
+    /// Return the header with the merkle root of the written items.
+    /// Fails with `BlockError::ItemCountMismatch` when fewer items than expected were written.
+    pub fn finish(mut self) -> Result<BlockHeader<H>, BlockError> {
+        match self.root {
+            Some(root) => {
+                self.header.merkle_root = Hash256::new(root);
+                Ok(self.header)
+            },
+            None => Err(BlockError::ItemCountMismatch(self.written)),
+        }
+    }
+
+    /// Push frames down to the node holding the next leaves.
+    fn descend(&mut self) {
+        while let Some(frame) = self.stack.last() {
+            if frame.size < 3 { break; }
+            let size = match frame.left {
+                None => frame.size / 2,
+                Some(_) => frame.size - frame.size / 2,
+            };
+            self.stack.push(Frame { size, left: None });
+        }
+    }
+
+    /// Finish the node on top of the stack with root `hash` and propagate it upwards.
+    fn complete(&mut self, mut hash: BlockHash) {
+        self.stack.pop();
+        loop {
+            match self.stack.last_mut() {
+                None => {
+                    self.root = Some(hash);
+                    return;
+                },
+                Some(parent) => match parent.left {
+                    None => {
+                        parent.left = Some(hash);
+                        self.descend();
+                        return;
+                    },
+                    Some(left) => {
+                        hash = merkle::merkle_hash(&left, &hash);
+                        self.stack.pop();
+                    },
+                },
+            }
+        }
+    }
+}
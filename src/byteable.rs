@@ -1,3 +1,5 @@
+use super::*;
+
 /// A trait for converting to bytes an object.
 pub trait Byteable {
     /// Return the memory representation as a byte array in little-endian byte order.
@@ -5,6 +7,38 @@ pub trait Byteable {
     // fn to_le_bytes (&self) -> [u8; usize];
 }
 
+/// A trait for rebuilding an object from the bytes returned by `Byteable::bytes`.
+pub trait FromBytes: Sized {
+    /// Rebuild the object from its byte representation.
+    /// Returns `None` when the bytes are not a valid representation.
+    fn from_bytes (bytes: &[u8]) -> Option<Self>;
+}
+
+/// Largest `FixedByteable::LEN` handled entirely with stack buffers.
+pub const MAX_FIXED_LEN: usize = 64;
+
+/// A trait for objects whose byte representation always has the same length.
+///
+/// `write` must produce the same bytes as `Byteable::bytes` for types implementing both.
+pub trait FixedByteable {
+    /// Length of the byte representation.
+    const LEN: usize;
+    /// Write the byte representation into `out`, which is exactly `LEN` bytes long.
+    fn write(&self, out: &mut [u8]);
+}
+
+impl Byteable for () {
+    fn bytes(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+impl FromBytes for () {
+    fn from_bytes(bytes: &[u8]) -> Option<()> {
+        if bytes.is_empty() { Some(()) } else { None }
+    }
+}
+
 impl Byteable for i32 {
     fn bytes(&self) -> Vec<u8> {
         let data = &self.to_le_bytes();
@@ -12,6 +46,44 @@ impl Byteable for i32 {
     }
 }
 
+impl FixedByteable for i32 {
+    const LEN: usize = 4;
+    fn write(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl FromBytes for i32 {
+    fn from_bytes(bytes: &[u8]) -> Option<i32> {
+        let mut data = [0u8; 4];
+        if bytes.len() != data.len() { return None; }
+        data.copy_from_slice(bytes);
+        Some(i32::from_le_bytes(data))
+    }
+}
+
+impl Byteable for BlockHash {
+    fn bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl FixedByteable for BlockHash {
+    const LEN: usize = BLOCKHASHLEN;
+    fn write(&self, out: &mut [u8]) {
+        out.copy_from_slice(self);
+    }
+}
+
+impl FromBytes for BlockHash {
+    fn from_bytes(bytes: &[u8]) -> Option<BlockHash> {
+        let mut data : BlockHash = [0; BLOCKHASHLEN];
+        if bytes.len() != data.len() { return None; }
+        data.copy_from_slice(bytes);
+        Some(data)
+    }
+}
+
 impl Byteable for String{
     fn bytes(&self) -> Vec<u8> {    
         let data = &self.as_bytes();
@@ -19,6 +91,12 @@ impl Byteable for String{
     }
 }
 
+impl FromBytes for String {
+    fn from_bytes(bytes: &[u8]) -> Option<String> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
 impl<T> Byteable for [T]
 where
     T: Byteable,
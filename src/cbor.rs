@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::iter::FromIterator;
+use super::*;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_SIMPLE: u8 = 7;
+
+/// Value of the CBOR data model (RFC 8949).
+#[derive(Clone, Debug, PartialEq)]
+pub enum CborValue {
+    /// unsigned integer `n`
+    Unsigned(u64),
+    /// negative integer `-1 - n`
+    Negative(u64),
+    /// byte string
+    Bytes(Vec<u8>),
+    /// UTF-8 text string
+    Text(String),
+    /// array of values
+    Array(Vec<CborValue>),
+    /// map of key/value pairs
+    Map(CborMap),
+    /// `true` or `false`
+    Bool(bool),
+    /// `null`
+    Null,
+    /// floating point number
+    Float(f64),
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    if argument < 24 {
+        out.push(major | argument as u8);
+    } else if argument <= 0xff {
+        out.push(major | 24);
+        out.push(argument as u8);
+    } else if argument <= 0xffff {
+        out.push(major | 25);
+        out.extend_from_slice(&(argument as u16).to_be_bytes());
+    } else if argument <= 0xffff_ffff {
+        out.push(major | 26);
+        out.extend_from_slice(&(argument as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+/// Half precision bits of `value` when the conversion is exact.
+fn to_half(value: f32) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = bits & 0x7f_ffff;
+    if value == 0.0 { return Some(sign); }
+    if value.is_infinite() { return Some(sign | 0x7c00); }
+    if (-14..=15).contains(&exponent) {
+        if mantissa & 0x1fff != 0 { return None; }
+        return Some(sign | (((exponent + 15) as u16) << 10) | (mantissa >> 13) as u16);
+    }
+    if (-24..-14).contains(&exponent) {
+        let significand = 0x80_0000 | mantissa;
+        let shift = (-1 - exponent) as u32;
+        if significand & ((1 << shift) - 1) != 0 { return None; }
+        return Some(sign | (significand >> shift) as u16);
+    }
+    None
+}
+
+fn write_float(out: &mut Vec<u8>, value: f64) {
+    let major = MAJOR_SIMPLE << 5;
+    if value.is_nan() {
+        out.extend_from_slice(&[major | 25, 0x7e, 0x00]);
+        return;
+    }
+    let single = value as f32;
+    if f64::from(single) == value {
+        if let Some(half) = to_half(single) {
+            out.push(major | 25);
+            out.extend_from_slice(&half.to_be_bytes());
+        } else {
+            out.push(major | 26);
+            out.extend_from_slice(&single.to_bits().to_be_bytes());
+        }
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&value.to_bits().to_be_bytes());
+    }
+}
+
+impl CborValue {
+    /// Encode the value with the core deterministic encoding of RFC 8949 §4.2.1.
+    ///
+    /// Integers and lengths use their shortest form, every length is definite,
+    /// floats use the shortest of half, single or double precision that keeps
+    /// their value (NaN is always `0xf97e00`), and map entries are sorted by the
+    /// bytewise order of their encoded keys, which are unique within a [`CborMap`].
+    pub fn to_deterministic_bytes(&self) -> Vec<u8> {
+        let mut out : Vec<u8> = Vec::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            CborValue::Unsigned(value) => write_head(out, MAJOR_UNSIGNED, *value),
+            CborValue::Negative(value) => write_head(out, MAJOR_NEGATIVE, *value),
+            CborValue::Bytes(bytes) => {
+                write_head(out, MAJOR_BYTES, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            },
+            CborValue::Text(text) => {
+                write_head(out, MAJOR_TEXT, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            },
+            CborValue::Array(items) => {
+                write_head(out, MAJOR_ARRAY, items.len() as u64);
+                for item in items { item.write(out); }
+            },
+            CborValue::Map(map) => {
+                write_head(out, MAJOR_MAP, map.len() as u64);
+                for (key, _, value) in &map.entries {
+                    out.extend_from_slice(key);
+                    value.write(out);
+                }
+            },
+            CborValue::Bool(false) => out.push(MAJOR_SIMPLE << 5 | 20),
+            CborValue::Bool(true) => out.push(MAJOR_SIMPLE << 5 | 21),
+            CborValue::Null => out.push(MAJOR_SIMPLE << 5 | 22),
+            CborValue::Float(value) => write_float(out, *value),
+        }
+    }
+}
+
+/// CBOR map with unique keys, kept in the order of their deterministic encoding.
+///
+/// Two keys are the same when they have the same deterministic encoding, so a map
+/// can not hold duplicate keys and always has a valid deterministic encoding.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let mut map = CborMap::new();
+/// assert_eq!(map.insert(CborValue::Text(String::from("b")), CborValue::Unsigned(1)), None);
+/// assert_eq!(map.insert(CborValue::Text(String::from("a")), CborValue::Unsigned(2)), None);
+/// assert_eq!(map.insert(CborValue::Text(String::from("b")), CborValue::Unsigned(3)), Some(CborValue::Unsigned(1)));
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map.get(&CborValue::Text(String::from("b"))), Some(&CborValue::Unsigned(3)));
+/// assert_eq!(CborValue::Map(map).to_deterministic_bytes(), vec![0xa2, 0x61, b'a', 0x02, 0x61, b'b', 0x03]);
+/// ```
+#[derive(Clone, Default, PartialEq)]
+pub struct CborMap {
+    // (encoded key, key, value) sorted by encoded key
+    entries: Vec<(Vec<u8>, CborValue, CborValue)>,
+}
+
+impl CborMap {
+    /// Constructs a new empty `CborMap`.
+    pub fn new() -> CborMap {
+        CborMap { entries: Vec::new() }
+    }
+
+    /// Insert `value` at `key`, returning the value it replaces.
+    pub fn insert(&mut self, key: CborValue, value: CborValue) -> Option<CborValue> {
+        let encoded = key.to_deterministic_bytes();
+        match self.entries.binary_search_by(|(other, _, _)| other.cmp(&encoded)) {
+            Ok(position) => Some(std::mem::replace(&mut self.entries[position].2, value)),
+            Err(position) => {
+                self.entries.insert(position, (encoded, key, value));
+                None
+            },
+        }
+    }
+
+    /// Return the value at `key`.
+    pub fn get(&self, key: &CborValue) -> Option<&CborValue> {
+        let encoded = key.to_deterministic_bytes();
+        self.entries.binary_search_by(|(other, _, _)| other.cmp(&encoded))
+            .ok()
+            .map(|position| &self.entries[position].2)
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the entries in the order of their encoded keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&CborValue, &CborValue)> {
+        self.entries.iter().map(|(_, key, value)| (key, value))
+    }
+}
+
+impl FromIterator<(CborValue, CborValue)> for CborMap {
+    /// Collect the entries, a later value replacing an earlier one at the same key.
+    fn from_iter<I: IntoIterator<Item = (CborValue, CborValue)>>(entries: I) -> CborMap {
+        let mut map = CborMap::new();
+        for (key, value) in entries { map.insert(key, value); }
+        map
+    }
+}
+
+impl fmt::Debug for CborMap {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A trait for converting an object to the CBOR data model.
+pub trait ToCbor {
+    /// Return the CBOR representation of the object.
+    fn to_cbor(&self) -> CborValue;
+}
+
+impl ToCbor for CborValue {
+    fn to_cbor(&self) -> CborValue {
+        self.clone()
+    }
+}
+
+macro_rules! unsigned_to_cbor {
+    ($($t:ty),*) => {$(
+        impl ToCbor for $t {
+            fn to_cbor(&self) -> CborValue {
+                CborValue::Unsigned(*self as u64)
+            }
+        }
+    )*};
+}
+unsigned_to_cbor!(u8, u16, u32, u64, usize);
+
+macro_rules! signed_to_cbor {
+    ($($t:ty),*) => {$(
+        impl ToCbor for $t {
+            fn to_cbor(&self) -> CborValue {
+                let value = *self as i64;
+                if value < 0 { CborValue::Negative(!value as u64) } else { CborValue::Unsigned(value as u64) }
+            }
+        }
+    )*};
+}
+signed_to_cbor!(i8, i16, i32, i64, isize);
+
+impl ToCbor for bool {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Bool(*self)
+    }
+}
+
+impl ToCbor for f32 {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Float(f64::from(*self))
+    }
+}
+
+impl ToCbor for f64 {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Float(*self)
+    }
+}
+
+impl ToCbor for str {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Text(self.to_string())
+    }
+}
+
+impl ToCbor for String {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Text(self.clone())
+    }
+}
+
+impl<T: ToCbor> ToCbor for Option<T> {
+    fn to_cbor(&self) -> CborValue {
+        match self { Some(value) => value.to_cbor(), None => CborValue::Null }
+    }
+}
+
+impl<T: ToCbor> ToCbor for [T] {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Array(self.iter().map(ToCbor::to_cbor).collect())
+    }
+}
+
+impl<T: ToCbor> ToCbor for Vec<T> {
+    fn to_cbor(&self) -> CborValue {
+        self[..].to_cbor()
+    }
+}
+
+impl<K: ToCbor, V: ToCbor> ToCbor for BTreeMap<K, V> {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Map(self.iter().map(|(key, value)| (key.to_cbor(), value.to_cbor())).collect())
+    }
+}
+
+impl<K: ToCbor, V: ToCbor, S> ToCbor for HashMap<K, V, S> {
+    fn to_cbor(&self) -> CborValue {
+        CborValue::Map(self.iter().map(|(key, value)| (key.to_cbor(), value.to_cbor())).collect())
+    }
+}
+
+/// Wrapper hashing structured data by its deterministic CBOR encoding.
+///
+/// Structured data coming from heterogeneous producers commits to the same
+/// leaf as long as it maps to the same CBOR value, whatever the original map
+/// ordering or integer widths were.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use std::collections::HashMap;
+/// use crate::blockchainblock::*;
+///
+/// let mut reading : HashMap<String, i64> = HashMap::new();
+/// reading.insert(String::from("sensor"), 7);
+/// reading.insert(String::from("celsius"), -12);
+///
+/// let same = CborValue::Map(vec![
+///     (CborValue::Text(String::from("sensor")), CborValue::Unsigned(7)),
+///     (CborValue::Text(String::from("celsius")), CborValue::Negative(11)),
+/// ].into_iter().collect());
+///
+/// let data = [CborByteable(reading.to_cbor())];
+/// let block : BlockchainBlock<CborByteable<CborValue>> = BlockchainBlock::new(None, &data, 4, 3);
+/// assert!(block.check_value_inblock(&CborByteable(same), 0));
+/// assert_eq!(CborByteable(1.5f64).bytes(), vec![0xf9, 0x3e, 0x00]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CborByteable<T>(pub T);
+
+impl<T: ToCbor> Byteable for CborByteable<T> {
+    fn bytes(&self) -> Vec<u8> {
+        self.0.to_cbor().to_deterministic_bytes()
+    }
+}
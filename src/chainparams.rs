@@ -0,0 +1,161 @@
+use super::*;
+
+/// Default limit on the encoded size of a block: 1 MiB.
+pub const DEFAULT_MAX_BLOCK_BYTES: usize = 1 << 20;
+
+/// Default tolerance on block timestamps ahead of the local clock: 2 hours.
+pub const DEFAULT_MAX_TIME_DRIFT: u64 = 2 * 60 * 60;
+
+/// Default limit on the depth of side branch blocks below the tip: 100 blocks.
+pub const DEFAULT_MAX_FORK_DEPTH: u64 = 100;
+
+/// Default limit on the number of blocks kept in side branches.
+pub const DEFAULT_MAX_SIDE_BLOCKS: usize = 1000;
+
+/// Default number of confirmations before a block is reported as confirmed.
+pub const DEFAULT_CONFIRMATIONS_REQUIRED: u64 = 6;
+
+/// Default limit on the number of orphan blocks kept for each peer.
+pub const DEFAULT_MAX_ORPHANS_PER_PEER: usize = 16;
+
+/// Default limit on the encoded size of a block received without being requested: 16 KiB.
+pub const DEFAULT_MAX_UNSOLICITED_BYTES: usize = 16 << 10;
+
+/// Parameters shared by every node of a chain.
+///
+/// Nodes using the same parameters derive the same genesis block.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+/// assert_eq!(params.genesis_nonce, 0);
+/// assert_eq!(params.max_block_bytes, DEFAULT_MAX_BLOCK_BYTES);
+/// assert_eq!(params.target, MAX_TARGET);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainParams {
+    /// creation time of the genesis block in *Unix time* format
+    pub genesis_timestamp: u64,
+    /// nonce of the genesis block
+    pub genesis_nonce: u64,
+    /// identifier of the chain, stamped in every header
+    pub chain_id: u32,
+    /// difficulty target of every block of the chain, the genesis block included
+    pub target: BlockHash,
+    /// largest encoded size of a block, as returned by `BlockchainBlock::encoded_size`
+    pub max_block_bytes: usize,
+    /// whether blocks without data are valid
+    pub allow_empty_blocks: bool,
+    /// seconds a block timestamp may be ahead of the local clock
+    pub max_time_drift: u64,
+    /// number of blocks a side branch block may be below the tip of a `Blockchain`
+    pub max_fork_depth: u64,
+    /// number of blocks a `Blockchain` keeps in side branches
+    pub max_side_blocks: usize,
+    /// number of confirmations a block of a `Blockchain` needs to be reported as
+    /// confirmed, the block itself counting as one
+    pub confirmations_required: u64,
+    /// number of blocks an `OrphanPool` keeps for each peer
+    pub max_orphans_per_peer: usize,
+    /// largest encoded size of a block an `OrphanPool` accepts without requesting it
+    pub max_unsolicited_bytes: usize,
+    /// hash of the genesis block, pinning its data; any data is accepted when `None`
+    pub genesis_hash: Option<Hash256>,
+}
+
+impl Default for ChainParams {
+    fn default() -> ChainParams {
+        ChainParams {
+            genesis_timestamp: 0,
+            genesis_nonce: 0,
+            chain_id: 0,
+            target: MAX_TARGET,
+            max_block_bytes: DEFAULT_MAX_BLOCK_BYTES,
+            allow_empty_blocks: true,
+            max_time_drift: DEFAULT_MAX_TIME_DRIFT,
+            max_fork_depth: DEFAULT_MAX_FORK_DEPTH,
+            max_side_blocks: DEFAULT_MAX_SIDE_BLOCKS,
+            confirmations_required: DEFAULT_CONFIRMATIONS_REQUIRED,
+            max_orphans_per_peer: DEFAULT_MAX_ORPHANS_PER_PEER,
+            max_unsolicited_bytes: DEFAULT_MAX_UNSOLICITED_BYTES,
+            genesis_hash: None,
+        }
+    }
+}
+
+impl ChainParams {
+    /// Derive a chain identifier from the genesis block: the first 4 bytes of the hash of
+    /// the genesis header, read as a little endian integer.
+    ///
+    /// # Description
+    ///
+    /// * `genesis_data` - Data of the genesis block.
+    ///
+    /// The identifier commits to exactly what the genesis header commits to: its
+    /// timestamp, nonce, version, target and, through the merkle root, its data. The
+    /// header is hashed with chain `0`, so `chain_id` itself is not part of the
+    /// derivation. Validation limits such as `max_block_bytes`, `allow_empty_blocks`,
+    /// `max_time_drift`, `max_fork_depth` or `max_orphans_per_peer` are local policy and
+    /// do not contribute.
+    pub fn derive_chain_id<T: Byteable + Clone>(&self, genesis_data: &[T]) -> u32 {
+        let unnamed = ChainParams { chain_id: 0, ..*self };
+        let hash = BlockchainBlock::genesis(&unnamed, genesis_data).curr_hash();
+        let mut id = [0u8; 4];
+        id.copy_from_slice(&hash.as_bytes()[..4]);
+        u32::from_le_bytes(id)
+    }
+
+    /// Set `chain_id` to the identifier derived from the genesis block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data = [String::from("genesis")];
+    /// let mainnet = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() }.with_derived_chain_id(&data);
+    /// let testnet = ChainParams { genesis_nonce: 1, ..mainnet }.with_derived_chain_id(&data);
+    /// assert_eq!(mainnet.chain_id, mainnet.derive_chain_id(&data));
+    /// assert_ne!(mainnet.chain_id, testnet.chain_id);
+    /// assert_ne!(mainnet.chain_id, mainnet.derive_chain_id(&[String::from("fork")]));
+    /// assert_eq!(mainnet.chain_id, ChainParams { max_block_bytes: 1000, ..mainnet }.derive_chain_id(&data));
+    /// ```
+    pub fn with_derived_chain_id<T: Byteable + Clone>(mut self, genesis_data: &[T]) -> ChainParams {
+        self.chain_id = self.derive_chain_id(genesis_data);
+        self
+    }
+
+    /// Check `other` describes the same chain: same genesis timestamp and nonce, chain
+    /// identifier, target and genesis hash. Local policy such as `max_block_bytes` may
+    /// differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+    /// assert!(params.same_chain(&ChainParams { max_block_bytes: 1000, max_time_drift: 0, ..params }));
+    /// assert!(!params.same_chain(&ChainParams { genesis_nonce: 1, ..params }));
+    /// ```
+    pub fn same_chain(&self, other: &ChainParams) -> bool {
+        self.genesis_timestamp == other.genesis_timestamp
+            && self.genesis_nonce == other.genesis_nonce
+            && self.chain_id == other.chain_id
+            && self.target == other.target
+            && self.genesis_hash == other.genesis_hash
+    }
+
+    /// Set `genesis_hash` to the hash of the genesis block holding `genesis_data`.
+    /// The hash covers the chain identifier, which must be set first.
+    pub fn with_genesis_hash<T: Byteable + Clone>(mut self, genesis_data: &[T]) -> ChainParams {
+        self.genesis_hash = Some(BlockchainBlock::genesis(&self, genesis_data).curr_hash());
+        self
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use super::*;
+
+/// Errors returned when registering a chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// Another chain is registered with this identifier.
+    Collision(u32),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::Collision(chain_id) => write!(formatter, "chain identifier {} is already in use", chain_id),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Registry of the chains hosted locally, keyed by chain identifier.
+///
+/// Registering two different chains with the same identifier fails, so blocks of one
+/// chain can not be mistaken for blocks of another. Chains are compared with
+/// [`ChainParams::same_chain`], so local policy does not cause collisions.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data = [String::from("genesis")];
+/// let mainnet = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() }.with_derived_chain_id(&data);
+/// let testnet = ChainParams { genesis_nonce: 1, ..mainnet }.with_derived_chain_id(&data);
+///
+/// let mut registry = ChainRegistry::new();
+/// assert_eq!(registry.register(mainnet), Ok(mainnet.chain_id));
+/// assert_eq!(registry.register(testnet), Ok(testnet.chain_id));
+/// assert_eq!(registry.register(mainnet), Ok(mainnet.chain_id));
+/// assert_eq!(registry.register(ChainParams { max_block_bytes: 1000, ..mainnet }), Ok(mainnet.chain_id));
+/// assert_eq!(registry.get(mainnet.chain_id), Some(&mainnet));
+///
+/// let clash = ChainParams { genesis_nonce: 2, chain_id: mainnet.chain_id, ..mainnet };
+/// assert_eq!(registry.register(clash), Err(RegistryError::Collision(mainnet.chain_id)));
+/// assert_eq!(registry.get(testnet.chain_id), Some(&testnet));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChainRegistry {
+    chains: Vec<ChainParams>,
+}
+
+impl ChainRegistry {
+    /// Constructs an empty `ChainRegistry`.
+    pub fn new() -> ChainRegistry {
+        ChainRegistry { chains: Vec::new() }
+    }
+
+    /// Register the chain described by `params` under `params.chain_id`.
+    /// Registering the same chain again is accepted and keeps the parameters registered
+    /// first.
+    pub fn register(&mut self, params: ChainParams) -> Result<u32, RegistryError> {
+        match self.get(params.chain_id) {
+            Some(registered) if registered.same_chain(&params) => Ok(params.chain_id),
+            Some(_) => Err(RegistryError::Collision(params.chain_id)),
+            None => {
+                self.chains.push(params);
+                Ok(params.chain_id)
+            },
+        }
+    }
+
+    /// Parameters of the chain registered with `chain_id`.
+    pub fn get(&self, chain_id: u32) -> Option<&ChainParams> {
+        self.chains.iter().find(|params| params.chain_id == chain_id)
+    }
+
+    /// Parameters of the chain `header` belongs to.
+    pub fn chain_of<H>(&self, header: &BlockHeader<H>) -> Option<&ChainParams> {
+        self.get(header.chain_id)
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time for block timestamps.
+///
+/// Blocks stamped through a `Clock` can be created deterministically in tests with
+/// [`FixedClock`].
+pub trait Clock {
+    /// Current time in seconds since 1970-01-01T00:00 UTC.
+    fn now(&self) -> u64;
+}
+
+/// Clock reading the system time.
+/// A system time before 1970 reads as `0`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0)
+    }
+}
+
+/// Clock always reading the same time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,117 @@
+use super::*;
+
+/// Length of the salt mixed into a commitment.
+pub const SALTLEN : usize = 32;
+/// Salt representation. Its fixed length keeps the split between data and salt unambiguous.
+pub type Salt = [u8; SALTLEN];
+
+/// Commitment to hidden data: `SHA256(data || salt)`.
+///
+/// Commitments are stored in a block first and the matching [`Reveal`] is stored later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Commitment {
+    /// hash of the data followed by the salt
+    pub hash: BlockHash,
+}
+
+impl Commitment {
+    /// Constructs the `Commitment` for `data` hidden with `salt`.
+    ///
+    /// # Description
+    ///
+    /// * `data` - Data to be committed.
+    /// * `salt` - Random salt. It must be kept secret until the reveal.
+    pub fn new<T: Byteable + ?Sized>(data: &T, salt: &Salt) -> Commitment {
+        let mut bytes = data.bytes();
+        bytes.extend_from_slice(salt);
+        Commitment { hash: sha256(&bytes) }
+    }
+}
+
+impl Byteable for Commitment {
+    fn bytes(&self) -> Vec<u8> {
+        self.hash.to_vec()
+    }
+}
+
+impl FixedByteable for Commitment {
+    const LEN: usize = BLOCKHASHLEN;
+    fn write(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.hash);
+    }
+}
+
+/// Reveal of the data and salt behind a [`Commitment`] stored in an earlier block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reveal<T> {
+    /// hash of the block holding the commitment
+    pub commit_block: Hash256,
+    /// position of the commitment in the data of that block
+    pub position: usize,
+    /// data that was committed
+    pub data: T,
+    /// salt used for the commitment
+    pub salt: Salt,
+}
+
+impl<T> Reveal<T>
+where
+    T: Byteable
+{
+    /// Constructs a new `Reveal<T>` linked to the commitment at `position` in `commit_block`.
+    ///
+    /// # Description
+    ///
+    /// * `commit_block` - Hash of the block holding the commitment.
+    /// * `position` - Position of the commitment in the block data.
+    /// * `data` - Data that was committed.
+    /// * `salt` - Salt used for the commitment.
+    pub fn new(commit_block: Hash256, position: usize, data: T, salt: Salt) -> Reveal<T> {
+        Reveal { commit_block, position, data, salt }
+    }
+
+    /// Recompute the commitment of the revealed data.
+    pub fn commitment(&self) -> Commitment {
+        Commitment::new(&self.data, &self.salt)
+    }
+
+    /// Check the reveal against the block it is linked to.
+    /// The block hash must match `commit_block` and the recomputed commitment must
+    /// be inside the block at `position`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let bid = String::from("{\"lot\": 7, \"amount\": 1200}");
+    /// let salt : Salt = [42; SALTLEN];
+    /// let commitments = [Commitment::new(&String::from("other bid"), &[1; SALTLEN]), Commitment::new(&bid, &salt)];
+    /// let commit_block : BlockchainBlock<Commitment> = BlockchainBlock::new(None, &commitments, 1524885322, 0);
+    ///
+    /// let reveal = Reveal::new(commit_block.curr_hash(), 1, bid, salt);
+    /// assert!(reveal.verify(&commit_block));
+    ///
+    /// let forged = Reveal::new(commit_block.curr_hash(), 1, String::from("{\"lot\": 7, \"amount\": 1}"), salt);
+    /// assert!(!forged.verify(&commit_block));
+    /// ```
+    pub fn verify(&self, commit_block: &BlockchainBlock<Commitment>) -> bool {
+        commit_block.curr_hash() == self.commit_block &&
+            commit_block.check_value_inblock(&self.commitment(), self.position)
+    }
+}
+
+impl<T> Byteable for Reveal<T>
+where
+    T: Byteable
+{
+    fn bytes(&self) -> Vec<u8> {
+        let mut data : Vec<u8> = Vec::new();
+        data.extend_from_slice(self.commit_block.as_bytes());
+        data.extend_from_slice(&(self.position as u64).to_le_bytes());
+        data.append(&mut self.data.bytes());
+        data.extend_from_slice(&self.salt);
+        data
+    }
+}
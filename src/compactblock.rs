@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use super::*;
+
+/// Length of a short item identifier.
+pub const SHORTIDLEN: usize = 6;
+/// Short identifier of a data item in a [`CompactBlock`].
+pub type ShortId = [u8; SHORTIDLEN];
+
+/// Block relayed as its header and short identifiers of its data items, in the spirit
+/// of BIP152 compact blocks.
+///
+/// Peers usually already hold most items of a new block, so they rebuild it from their
+/// own pool and only request the missing ones. A short identifier is the first
+/// `SHORTIDLEN` bytes of the sha256 of the block hash, the salt in little endian and the
+/// item bytes; the salt is chosen by the sender so identifiers differ between relays.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data = [String::from("a"), String::from("b"), String::from("c")];
+/// let block : BlockchainBlock<String> = BlockchainBlock::new(None, &data, 4, 3);
+/// let compact = block.to_compact(42);
+/// assert_eq!(compact.short_ids.len(), 3);
+///
+/// let pool = vec![String::from("c"), String::from("x"), String::from("a")];
+/// assert_eq!(compact.reconstruct(&pool), Err(BlockError::MissingItems(vec![1])));
+///
+/// let mut pool = pool;
+/// pool.push(String::from("b"));
+/// let rebuilt : OwnedBlock<String> = compact.reconstruct(&pool).unwrap();
+/// assert_eq!(rebuilt, block);
+/// assert_eq!(rebuilt.data(), &data);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactBlock<H = ()> {
+    /// header of the block
+    pub header: BlockHeader<H>,
+    /// salt of the short identifiers
+    pub salt: u64,
+    /// short identifiers of the data items, in block order
+    pub short_ids: Vec<ShortId>,
+}
+
+impl<H: Byteable> CompactBlock<H> {
+    /// Short identifier of `item` in this compact block.
+    pub fn short_id<T: Byteable>(&self, item: &T) -> ShortId {
+        short_id(&self.header.hash(), self.salt, item)
+    }
+
+    /// Positions of the items not found in `pool`, or found more than once with
+    /// different contents.
+    pub fn missing<T: Byteable>(&self, pool: &[T]) -> Vec<usize> {
+        let index = self.index(pool);
+        self.short_ids.iter().enumerate()
+            .filter(|(_, id)| !matches!(index.get(*id), Some(Some(_))))
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Rebuild the block from the items in `pool`.
+    ///
+    /// Fails with `BlockError::MissingItems` listing the positions of the items to
+    /// request, or with `BlockError::MerkleMismatch` when a short identifier collision
+    /// picked a wrong item.
+    pub fn reconstruct<T>(&self, pool: &[T]) -> Result<OwnedBlock<T, H>, BlockError>
+    where
+        T: Byteable + Clone + 'static,
+        H: Clone
+    {
+        let index = self.index(pool);
+        let mut data : Vec<T> = Vec::with_capacity(self.short_ids.len());
+        let mut missing : Vec<usize> = Vec::new();
+        for (position, id) in self.short_ids.iter().enumerate() {
+            match index.get(id) {
+                Some(Some(item)) => data.push((*item).clone()),
+                _ => missing.push(position),
+            }
+        }
+        if !missing.is_empty() { return Err(BlockError::MissingItems(missing)); }
+        let body = BlockBody::owned(data);
+        if !body.matches(&self.header) { return Err(BlockError::MerkleMismatch); }
+        Ok(BlockchainBlock::from_parts(self.header.clone(), body))
+    }
+
+    /// Map short identifiers to the pool item having it, `None` when ambiguous.
+    fn index<'p, T: Byteable>(&self, pool: &'p [T]) -> HashMap<ShortId, Option<&'p T>> {
+        let block_hash = self.header.hash();
+        let mut index : HashMap<ShortId, (Option<&'p T>, Vec<u8>)> = HashMap::new();
+        for item in pool {
+            let bytes = item.bytes();
+            let entry = index.entry(short_id(&block_hash, self.salt, item)).or_insert((Some(item), bytes.clone()));
+            if entry.1 != bytes { entry.0 = None; }
+        }
+        index.into_iter().map(|(id, (item, _))| (id, item)).collect()
+    }
+}
+
+impl<'a, T, H> BlockchainBlock<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable + Clone
+{
+    /// Compact representation of the block, see [`CompactBlock`].
+    ///
+    /// # Description
+    ///
+    /// * `salt` - Salt of the short identifiers, chosen by the sender.
+    pub fn to_compact(&self, salt: u64) -> CompactBlock<H> {
+        CompactBlock {
+            header: self.header().clone(),
+            salt,
+            short_ids: self.iter().map(|item| short_id(&self.curr_hash(), salt, item)).collect(),
+        }
+    }
+}
+
+fn short_id<T: Byteable>(block_hash: &Hash256, salt: u64, item: &T) -> ShortId {
+    let mut bytes : Vec<u8> = Vec::with_capacity(BLOCKHASHLEN + 8);
+    bytes.extend_from_slice(block_hash.as_bytes());
+    bytes.extend_from_slice(&salt.to_le_bytes());
+    bytes.append(&mut item.bytes());
+    let mut id : ShortId = [0; SHORTIDLEN];
+    id.copy_from_slice(&sha256(&bytes)[..SHORTIDLEN]);
+    id
+}
@@ -0,0 +1,106 @@
+use super::*;
+
+fn iterate(mut state: BlockHash, ticks: u64) -> BlockHash {
+    for _ in 0..ticks {
+        state = sha256(&state);
+    }
+    state
+}
+
+/// Verifiable delay sequence built by iterated SHA256 hashing.
+///
+/// Every tick hashes the previous state, so producing `n` ticks takes `n`
+/// sequential hash evaluations. Seeded with the hash of the previous block,
+/// the resulting [`DelayProof`] is a lower bound on the time elapsed since that
+/// block that does not depend on wall-clock timestamps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelaySequence {
+    seed: BlockHash,
+    ticks: u64,
+    state: BlockHash,
+}
+
+impl DelaySequence {
+    /// Constructs a new `DelaySequence` starting at `seed`.
+    ///
+    /// # Description
+    ///
+    /// * `seed` - Starting point, usually the hash of the previous block.
+    pub fn new(seed: BlockHash) -> DelaySequence {
+        DelaySequence { seed, ticks: 0, state: seed }
+    }
+
+    /// Advance the sequence by `ticks` hash evaluations.
+    /// Panics when the total number of ticks does not fit in a `u64`.
+    pub fn tick(&mut self, ticks: u64) {
+        let total = self.ticks.checked_add(ticks).expect("delay sequence tick count overflows");
+        self.state = iterate(self.state, ticks);
+        self.ticks = total;
+    }
+
+    /// Number of ticks performed since the seed.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Seed the sequence started from.
+    pub fn seed(&self) -> &BlockHash {
+        &self.seed
+    }
+
+    /// Proof of the ticks performed so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let data : [i32; 1] = [5];
+    /// let parent : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+    ///
+    /// let mut sequence = DelaySequence::new(parent.curr_hash().into());
+    /// sequence.tick(1000);
+    /// let proof = sequence.proof();
+    ///
+    /// let proofs = [proof];
+    /// let child : BlockchainBlock<DelayProof> = BlockchainBlock::new(Some(parent.curr_hash()), &proofs, 5, 0);
+    /// assert!(child.data()[0].verify(parent.curr_hash().as_bytes(), 1000));
+    /// assert!(!child.data()[0].verify(&[0; BLOCKHASHLEN], 1000));
+    /// assert!(!child.data()[0].verify(parent.curr_hash().as_bytes(), 999));
+    /// ```
+    pub fn proof(&self) -> DelayProof {
+        DelayProof { ticks: self.ticks, output: self.state }
+    }
+}
+
+/// Output of a [`DelaySequence`] after a number of ticks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DelayProof {
+    /// number of sequential hash evaluations
+    pub ticks: u64,
+    /// state of the sequence after the ticks
+    pub output: BlockHash,
+}
+
+impl DelayProof {
+    /// Check the proof by recomputing the sequence from `seed`.
+    ///
+    /// # Description
+    ///
+    /// * `seed` - Starting point of the sequence.
+    /// * `max_ticks` - Largest number of ticks accepted. Verification is as slow as
+    ///   producing the proof, so proofs claiming more ticks are rejected without
+    ///   recomputing anything.
+    pub fn verify(&self, seed: &BlockHash, max_ticks: u64) -> bool {
+        self.ticks <= max_ticks && iterate(*seed, self.ticks) == self.output
+    }
+}
+
+impl Byteable for DelayProof {
+    fn bytes(&self) -> Vec<u8> {
+        let mut data = self.ticks.to_le_bytes().to_vec();
+        data.extend_from_slice(&self.output);
+        data
+    }
+}
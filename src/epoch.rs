@@ -0,0 +1,186 @@
+//! Periodic summaries of a chain.
+//!
+//! A chain is cut in epochs of a fixed number of blocks, the epoch `e` of `len` blocks
+//! holding the heights `e * len` to `(e + 1) * len - 1`. The [`EpochSummary`] of an
+//! epoch commits to the hashes of its blocks with a merkle root and records statistics
+//! of its data, so an auditor can check a long range by checking one summary and
+//! spot checking a few blocks with [`EpochSummary::block_proof`].
+
+use std::convert::TryFrom;
+use super::*;
+use crate::inclusion;
+use crate::merkle;
+
+/// Length of the encoding of an [`EpochSummary`].
+const SUMMARYLEN: usize = 8 * 6 + BLOCKHASHLEN;
+
+/// Summary of consecutive blocks of a chain.
+///
+/// # Description
+///
+/// The summary is a plain value: store it as an item of a block of the chain or of
+/// another chain to get a summary block. [`closed`](EpochSummary::closed) returns the
+/// summary of the epoch the tip of a chain closes, so a node calling it after each
+/// block creates a summary every `len` blocks.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+/// let mut chain : Blockchain<BlockHash> = Blockchain::with_params(params);
+/// let mut summaries : Vec<EpochSummary> = Vec::new();
+/// for block in fixtures::chain(&params, 7, 10) {
+///     chain.push(block.seal().unwrap()).unwrap();
+///     summaries.extend(EpochSummary::closed(&chain, 4));
+/// }
+/// assert_eq!(summaries.len(), 2);
+/// let summary = summaries[1];
+/// assert_eq!((summary.first_height, summary.len), (4, 4));
+/// assert_eq!(summary.item_count, 2 + 3 + 1 + 2);
+/// assert_eq!(summary.last_timestamp - summary.first_timestamp, 3 * 600);
+/// assert!(summary.verify(&chain));
+///
+/// // spot check of the block at height 6
+/// let block = chain.at_height(6).unwrap();
+/// let proof = summary.block_proof(&chain, 6).unwrap();
+/// assert!(summary.check_block(block, &proof));
+/// assert!(!summary.check_block(chain.at_height(5).unwrap(), &proof));
+/// assert!(summary.block_proof(&chain, 8).is_none());
+///
+/// assert_eq!(EpochSummary::from_bytes(&summary.bytes()), Some(summary));
+/// assert_eq!(EpochSummary::of(&chain, 8, 4), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EpochSummary {
+    /// height of the first block of the epoch
+    pub first_height: u64,
+    /// number of blocks of the epoch
+    pub len: u64,
+    /// merkle root of the hashes of the blocks, from the first to the last
+    pub root: Hash256,
+    /// number of items in the blocks
+    pub item_count: u64,
+    /// encoded size of the blocks, in bytes
+    pub encoded_bytes: u64,
+    /// timestamp of the first block
+    pub first_timestamp: u64,
+    /// timestamp of the last block
+    pub last_timestamp: u64,
+}
+
+impl EpochSummary {
+    /// Summarize the `len` blocks of `chain` from height `first_height`.
+    /// Returns `None` when `len` is `0` or the chain does not hold all the blocks.
+    pub fn of<T, H>(chain: &Blockchain<T, H>, first_height: u64, len: u64) -> Option<EpochSummary>
+    where
+        T: Byteable + Clone + 'static,
+        H: Byteable
+    {
+        let blocks = blocks(chain, first_height, len)?;
+        Some(EpochSummary {
+            first_height,
+            len,
+            root: blocks_root(&blocks),
+            item_count: blocks.iter().map(|block| block.data().len() as u64).sum(),
+            encoded_bytes: blocks.iter().map(|block| block.encoded_size() as u64).sum(),
+            first_timestamp: blocks[0].timestamp(),
+            last_timestamp: blocks[blocks.len() - 1].timestamp(),
+        })
+    }
+
+    /// Summarize the epoch of `len` blocks ending with the tip of `chain`, `None` when
+    /// the tip does not close an epoch.
+    pub fn closed<T, H>(chain: &Blockchain<T, H>, len: u64) -> Option<EpochSummary>
+    where
+        T: Byteable + Clone + 'static,
+        H: Byteable
+    {
+        let next = chain.tip()?.height().checked_add(1)?;
+        if len == 0 || next % len != 0 { return None; }
+        EpochSummary::of(chain, next - len, len)
+    }
+
+    /// Check the summary is the one of the blocks of `chain`.
+    pub fn verify<T, H>(&self, chain: &Blockchain<T, H>) -> bool
+    where
+        T: Byteable + Clone + 'static,
+        H: Byteable
+    {
+        EpochSummary::of(chain, self.first_height, self.len).as_ref() == Some(self)
+    }
+
+    /// Build the proof that the block of `chain` at `height` is one of the summarized
+    /// blocks. Returns `None` when `height` is not in the epoch or the chain does not
+    /// hold all its blocks.
+    pub fn block_proof<T: Clone + 'static, H>(&self, chain: &Blockchain<T, H>, height: u64) -> Option<InclusionProof> {
+        if height < self.first_height { return None; }
+        let blocks = blocks(chain, self.first_height, self.len)?;
+        let hashes : Vec<Hash256> = blocks.iter().map(|block| block.curr_hash()).collect();
+        inclusion::prove(&hashes, usize::try_from(height - self.first_height).ok()?)
+    }
+
+    /// Check `proof` proves `block` is the summarized block at its height.
+    pub fn check_block<T: Clone, H>(&self, block: &BlockchainBlock<T, H>, proof: &InclusionProof) -> bool {
+        block.height().checked_sub(self.first_height) == Some(proof.position)
+            && proof.count == self.len
+            && proof.verify(&block.curr_hash(), &self.root)
+    }
+}
+
+/// Return the `len` blocks of `chain` from height `first_height`, `None` when `len` is
+/// `0` or the chain does not hold all the blocks.
+pub(crate) fn blocks<T: Clone + 'static, H>(chain: &Blockchain<T, H>, first_height: u64, len: u64) -> Option<Vec<&SealedBlock<'static, T, H>>> {
+    if len == 0 { return None; }
+    first_height.checked_add(len - 1)?;
+    (first_height..first_height + len).map(|height| chain.at_height(height)).collect()
+}
+
+/// Merkle root of the hashes of `blocks`.
+pub(crate) fn blocks_root<T: Clone + 'static, H>(blocks: &[&SealedBlock<'static, T, H>]) -> Hash256 {
+    let hashes : Vec<Hash256> = blocks.iter().map(|block| block.curr_hash()).collect();
+    merkle::merkle_root(&hashes).into()
+}
+
+impl Byteable for EpochSummary {
+    fn bytes(&self) -> Vec<u8> {
+        let mut out = vec![0; SUMMARYLEN];
+        self.write(&mut out);
+        out
+    }
+}
+
+impl FixedByteable for EpochSummary {
+    const LEN: usize = SUMMARYLEN;
+    fn write(&self, out: &mut [u8]) {
+        out[..8].copy_from_slice(&self.first_height.to_le_bytes());
+        out[8..16].copy_from_slice(&self.len.to_le_bytes());
+        out[16..48].copy_from_slice(self.root.as_bytes());
+        out[48..56].copy_from_slice(&self.item_count.to_le_bytes());
+        out[56..64].copy_from_slice(&self.encoded_bytes.to_le_bytes());
+        out[64..72].copy_from_slice(&self.first_timestamp.to_le_bytes());
+        out[72..].copy_from_slice(&self.last_timestamp.to_le_bytes());
+    }
+}
+
+impl FromBytes for EpochSummary {
+    fn from_bytes(bytes: &[u8]) -> Option<EpochSummary> {
+        if bytes.len() != SUMMARYLEN { return None; }
+        let read = |start: usize| -> u64 {
+            let mut value = [0; 8];
+            value.copy_from_slice(&bytes[start..start + 8]);
+            u64::from_le_bytes(value)
+        };
+        Some(EpochSummary {
+            first_height: read(0),
+            len: read(8),
+            root: Hash256::from_bytes(&bytes[16..48])?,
+            item_count: read(48),
+            encoded_bytes: read(56),
+            first_timestamp: read(64),
+            last_timestamp: read(72),
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+use crate::json::JsonError;
+
+/// Errors returned when decoding or validating a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockError {
+    /// The input ended before the block was complete.
+    Truncated,
+    /// Bytes remain after the end of the block.
+    TrailingBytes,
+    /// The input uses flags or encodings the encoder never produces.
+    Malformed,
+    /// The data item at the given position could not be decoded.
+    InvalidItem(usize),
+    /// The merkle root of the data does not match the one in the header.
+    MerkleMismatch,
+    /// The stored hash does not match the one calculated from the header.
+    HashMismatch,
+    /// The header version is not supported.
+    BadVersion(u8),
+    /// The encoded block is larger than the limit, holding the encoded size.
+    TooLarge(usize),
+    /// The block has no data and the chain does not allow empty blocks.
+    EmptyData,
+    /// The block has no previous hash and is not the genesis block.
+    MissingPrevHash,
+    /// The timestamp is before the genesis block or too far ahead of the local clock.
+    BadTimestamp(u64),
+    /// The number of data items differs from the announced one, holding the number of items written.
+    ItemCountMismatch(u64),
+    /// Data items are missing to rebuild the block, holding their positions.
+    MissingItems(Vec<usize>),
+    /// The text of a JSON export is not valid JSON.
+    InvalidJson(JsonError),
+    /// The parent block is at the largest height, so the block has no height.
+    HeightOverflow,
+    /// The parent block belongs to another chain, holding its chain identifier.
+    ChainMismatch(u32),
+    /// The binary encoding uses a format revision this version does not understand.
+    UnsupportedFormat(u8),
+    /// The block hash exceeds the difficulty target of the header.
+    TargetNotMet,
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockError::Truncated => write!(formatter, "block is truncated"),
+            BlockError::TrailingBytes => write!(formatter, "trailing bytes after block"),
+            BlockError::Malformed => write!(formatter, "block is malformed"),
+            BlockError::InvalidItem(position) => write!(formatter, "invalid data item at position {}", position),
+            BlockError::MerkleMismatch => write!(formatter, "merkle root does not match the block data"),
+            BlockError::HashMismatch => write!(formatter, "block hash does not match the header"),
+            BlockError::BadVersion(version) => write!(formatter, "unsupported block version {}", version),
+            BlockError::TooLarge(size) => write!(formatter, "block of {} bytes exceeds the size limit", size),
+            BlockError::EmptyData => write!(formatter, "block has no data"),
+            BlockError::MissingPrevHash => write!(formatter, "block has no previous hash and is not the genesis block"),
+            BlockError::BadTimestamp(timestamp) => write!(formatter, "block timestamp {} is out of range", timestamp),
+            BlockError::ItemCountMismatch(written) => write!(formatter, "{} data items do not match the announced count", written),
+            BlockError::MissingItems(positions) => write!(formatter, "{} data items are missing", positions.len()),
+            BlockError::InvalidJson(error) => write!(formatter, "invalid JSON block: {}", error),
+            BlockError::HeightOverflow => write!(formatter, "parent block is at the largest height"),
+            BlockError::ChainMismatch(chain_id) => write!(formatter, "parent block belongs to chain {}", chain_id),
+            BlockError::UnsupportedFormat(format) => write!(formatter, "unsupported block format {}", format),
+            BlockError::TargetNotMet => write!(formatter, "block hash does not meet the difficulty target"),
+        }
+    }
+}
+
+impl std::error::Error for BlockError {}
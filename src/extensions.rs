@@ -0,0 +1,163 @@
+//! Typed header extensions.
+//!
+//! [`Extensions`] is a header extension holding any number of entries, each made of
+//! an identifier and opaque bytes. Applications define typed entries, such as a state
+//! root or a beacon value, by implementing [`Extension`], so new entries can be added
+//! without changing the header layout. The extensions are encoded as:
+//!
+//! * nothing when there is no entry, so the hash is the one of a block without
+//!   extension;
+//! * otherwise the number of entries as a LEB128 varint, followed by each entry in
+//!   increasing identifier order: the identifier as a 2-byte little endian integer,
+//!   the length of the value as a varint and the value.
+
+use super::*;
+use crate::varint;
+
+/// A typed entry of [`Extensions`].
+///
+/// The identifier tells the entries apart, it must be unique among the extensions an
+/// application defines. The value is stored as its `Byteable::bytes`.
+pub trait Extension: Byteable + FromBytes {
+    /// Identifier of the entry.
+    const ID: u16;
+}
+
+/// Header extension made of entries keyed by identifier.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct StateRoot(BlockHash);
+///
+/// impl Byteable for StateRoot {
+///     fn bytes(&self) -> Vec<u8> { self.0.to_vec() }
+/// }
+/// impl FromBytes for StateRoot {
+///     fn from_bytes(bytes: &[u8]) -> Option<StateRoot> { BlockHash::from_bytes(bytes).map(StateRoot) }
+/// }
+/// impl Extension for StateRoot {
+///     const ID: u16 = 1;
+/// }
+///
+/// let mut extensions = Extensions::new();
+/// extensions.insert(&StateRoot([7; BLOCKHASHLEN]));
+/// extensions.insert_raw(9, vec![1, 2, 3]);
+///
+/// let data : [i32; 1] = [5];
+/// let block : BlockchainBlock<i32, Extensions> = BlockchainBlock::builder().extension(extensions).data(&data).build();
+/// assert_eq!(block.extension().get::<StateRoot>(), Some(StateRoot([7; BLOCKHASHLEN])));
+/// assert_eq!(block.extension().get_raw(9), Some(&[1, 2, 3][..]));
+///
+/// let decoded : OwnedBlock<i32, Extensions> = BlockchainBlock::from_bytes(&block.to_bytes()).unwrap();
+/// assert_eq!(decoded, block);
+///
+/// let plain : BlockchainBlock<i32, Extensions> = BlockchainBlock::builder().extension(Extensions::new()).data(&data).build();
+/// assert_eq!(plain.curr_hash(), BlockchainBlock::builder().data(&data).build().curr_hash());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Extensions {
+    // sorted by identifier, without duplicates
+    entries: Vec<(u16, Vec<u8>)>,
+}
+
+impl Extensions {
+    /// Constructs an empty `Extensions`.
+    pub fn new() -> Extensions {
+        Extensions { entries: Vec::new() }
+    }
+
+    /// Set the entry of type `E` to `value`, returning the bytes it replaces.
+    pub fn insert<E: Extension>(&mut self, value: &E) -> Option<Vec<u8>> {
+        self.insert_raw(E::ID, value.bytes())
+    }
+
+    /// Return the entry of type `E`, `None` when it is missing or its bytes are not a
+    /// valid `E`.
+    pub fn get<E: Extension>(&self) -> Option<E> {
+        self.get_raw(E::ID).and_then(E::from_bytes)
+    }
+
+    /// Set the entry `id` to `value`, returning the bytes it replaces.
+    pub fn insert_raw(&mut self, id: u16, value: Vec<u8>) -> Option<Vec<u8>> {
+        match self.entries.binary_search_by_key(&id, |(other, _)| *other) {
+            Ok(position) => Some(std::mem::replace(&mut self.entries[position].1, value)),
+            Err(position) => {
+                self.entries.insert(position, (id, value));
+                None
+            },
+        }
+    }
+
+    /// Return the bytes of the entry `id`.
+    pub fn get_raw(&self, id: u16) -> Option<&[u8]> {
+        self.entries.binary_search_by_key(&id, |(other, _)| *other)
+            .ok()
+            .map(|position| &self.entries[position].1[..])
+    }
+
+    /// Remove the entry `id`, returning its bytes.
+    pub fn remove(&mut self, id: u16) -> Option<Vec<u8>> {
+        self.entries.binary_search_by_key(&id, |(other, _)| *other)
+            .ok()
+            .map(|position| self.entries.remove(position).1)
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check there is no entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the identifiers and bytes of the entries, in increasing identifier order.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &[u8])> {
+        self.entries.iter().map(|(id, value)| (*id, &value[..]))
+    }
+}
+
+impl Byteable for Extensions {
+    fn bytes(&self) -> Vec<u8> {
+        let mut out : Vec<u8> = Vec::new();
+        if self.entries.is_empty() { return out; }
+        varint::write_u64(&mut out, self.entries.len() as u64);
+        for (id, value) in &self.entries {
+            out.extend_from_slice(&id.to_le_bytes());
+            varint::write_u64(&mut out, value.len() as u64);
+            out.extend_from_slice(value);
+        }
+        out
+    }
+}
+
+impl FromBytes for Extensions {
+    /// Only the canonical encoding is accepted: at least one entry, identifiers in
+    /// increasing order and no trailing bytes.
+    fn from_bytes(bytes: &[u8]) -> Option<Extensions> {
+        if bytes.is_empty() { return Some(Extensions::new()); }
+        let mut pos = 0;
+        let count = varint::read_u64(bytes, &mut pos)?;
+        if count == 0 { return None; }
+        let mut entries : Vec<(u16, Vec<u8>)> = Vec::new();
+        for _ in 0..count {
+            let id = bytes.get(pos..pos + 2)?;
+            let id = u16::from_le_bytes([id[0], id[1]]);
+            pos += 2;
+            if entries.last().is_some_and(|(last, _)| *last >= id) { return None; }
+            let len = varint::read_u64(bytes, &mut pos)?;
+            if len > (bytes.len() - pos) as u64 { return None; }
+            let end = pos + len as usize;
+            entries.push((id, bytes.get(pos..end)?.to_vec()));
+            pos = end;
+        }
+        if pos != bytes.len() { return None; }
+        Some(Extensions { entries })
+    }
+}
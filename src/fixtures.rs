@@ -0,0 +1,124 @@
+//! Deterministic fixtures for golden-file tests.
+//!
+//! Every fixture only depends on a seed and the chain parameters, so an application can
+//! commit the output of [`golden`] as a file and compare it after upgrading this crate:
+//! any change to the hashes or to the binary, JSON or header stream encodings shows up
+//! as a difference.
+//!
+//! The chain of [`chain`] starts with the genesis block of the parameters. The block at
+//! height `h` is created `600 * h` seconds after the genesis timestamp, saturating at
+//! `u64::MAX`, with nonce `0` and holds `1 + h % 3` items, item `i` being the seed, `h`
+//! and `i` as 8-byte little endian integers followed by zeros.
+
+use super::*;
+use crate::hex;
+
+/// Number of ticks of the delay proofs of [`delay_proofs`].
+pub const DELAY_TICKS: u64 = 16;
+
+fn items(seed: u64, height: u64) -> Vec<BlockHash> {
+    (0..1 + height % 3).map(|index| {
+        let mut item = [0; BLOCKHASHLEN];
+        item[..8].copy_from_slice(&seed.to_le_bytes());
+        item[8..16].copy_from_slice(&height.to_le_bytes());
+        item[16..24].copy_from_slice(&index.to_le_bytes());
+        item
+    }).collect()
+}
+
+/// Build the first `len` blocks of the fixture chain for `params` and `seed`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+/// let chain = fixtures::chain(&params, 7, 4);
+/// assert_eq!(chain.len(), 4);
+/// assert_eq!(*chain[0].header(), *BlockchainBlock::genesis(&params, chain[0].data()).header());
+/// assert_eq!(chain[3].data().len(), 1);
+/// assert!(chain.iter().all(|block| block.verify().is_ok()));
+///
+/// let headers : Vec<BlockHeader> = chain.iter().map(|block| *block.header()).collect();
+/// assert!(verify::header_chain(&headers));
+/// assert_eq!(fixtures::chain(&params, 7, 4), chain);
+/// assert_ne!(fixtures::chain(&params, 8, 4), chain);
+///
+/// let late = ChainParams { genesis_timestamp: u64::MAX - 1000, ..params };
+/// assert_eq!(fixtures::chain(&late, 7, 4)[3].timestamp(), u64::MAX);
+/// ```
+pub fn chain(params: &ChainParams, seed: u64, len: usize) -> Vec<OwnedBlock<BlockHash>> {
+    let mut blocks : Vec<OwnedBlock<BlockHash>> = Vec::with_capacity(len);
+    for height in 0..len as u64 {
+        let data = items(seed, height);
+        let block = match blocks.last() {
+            None => BlockchainBlock::genesis(params, &data).into_owned(),
+            Some(parent) => BlockchainBlock::builder()
+                .parent(parent.header())
+                .timestamp(params.genesis_timestamp.saturating_add(height.saturating_mul(600)))
+                .data(&data)
+                .build()
+                .into_owned(),
+        };
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Build a delay proof of [`DELAY_TICKS`] ticks seeded with the hash of each block of `chain`.
+pub fn delay_proofs<T: Clone>(chain: &[BlockchainBlock<T>]) -> Vec<DelayProof> {
+    chain.iter().map(|block| {
+        let mut sequence = DelaySequence::new(block.curr_hash().into());
+        sequence.tick(DELAY_TICKS);
+        sequence.proof()
+    }).collect()
+}
+
+/// Render the golden file of the first `len` blocks of the fixture chain for `params`
+/// and `seed`.
+///
+/// # Description
+///
+/// The text has one line per artifact, each made of space separated fields:
+///
+/// * `block <height> hash <hex>` - hash of the block;
+/// * `block <height> bytes <hex>` - binary encoding of the block, see
+///   [`to_bytes`](BlockchainBlock::to_bytes);
+/// * `block <height> json <json>` - JSON export of the block, see
+///   [`to_json`](BlockchainBlock::to_json);
+/// * `proof <height> <hex>` - bytes of the delay proof of the block, see [`delay_proofs`];
+/// * `headers <hex>` - header stream of the whole chain, see [`encode_headers`].
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+/// let golden = fixtures::golden(&params, 7, 3);
+/// assert_eq!(golden.lines().count(), 3 * 4 + 1);
+/// assert!(golden.starts_with("block 0 hash 8006c7eadfa6686d02f28b0845ff3d95036bd6af7c6a8e767c784cc84423242b\n"));
+/// assert!(golden.lines().last().unwrap().starts_with("headers 424853"));
+/// assert_eq!(fixtures::golden(&params, 7, 3), golden);
+///
+/// let other = ChainParams { genesis_nonce: 1, ..params };
+/// assert_ne!(fixtures::golden(&other, 7, 3), golden);
+/// ```
+pub fn golden(params: &ChainParams, seed: u64, len: usize) -> String {
+    let blocks = chain(params, seed, len);
+    let proofs = delay_proofs(&blocks);
+    let mut out = String::new();
+    for (block, proof) in blocks.iter().zip(&proofs) {
+        let height = block.height();
+        out.push_str(&format!("block {} hash {}\n", height, block.curr_hash().to_hex()));
+        out.push_str(&format!("block {} bytes {}\n", height, hex::to_hex(&block.to_bytes())));
+        out.push_str(&format!("block {} json {}\n", height, block.to_json()));
+        out.push_str(&format!("proof {} {}\n", height, hex::to_hex(&proof.bytes())));
+    }
+    let headers : Vec<BlockHeader> = blocks.iter().map(|block| *block.header()).collect();
+    out.push_str(&format!("headers {}\n", hex::to_hex(&encode_headers(&headers))));
+    out
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use std::str::FromStr;
+use super::*;
+use crate::hex;
+
+/// A 256-bit hash identifying a block or committing to its data.
+///
+/// # Description
+///
+/// Wraps a [`BlockHash`] to print and parse it as hexadecimal. Comparing two
+/// hashes for equality looks at every byte, so the time taken does not tell how
+/// many leading bytes matched.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 2] = [1, 2];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// let text = block.curr_hash().to_hex();
+/// assert_eq!(text.len(), 2 * BLOCKHASHLEN);
+/// assert_eq!(text.parse::<Hash256>(), Ok(block.curr_hash()));
+/// assert_eq!(format!("{}", block.curr_hash()), text);
+/// assert_eq!("00ff".parse::<Hash256>(), Err(ParseHashError::BadLength(4)));
+/// ```
+#[derive(Clone, Copy, Eq)]
+pub struct Hash256(BlockHash);
+
+impl Hash256 {
+    /// The all-zeros hash.
+    pub const ZERO: Hash256 = Hash256([0; BLOCKHASHLEN]);
+
+    /// Wraps `bytes`.
+    pub const fn new(bytes: BlockHash) -> Hash256 {
+        Hash256(bytes)
+    }
+
+    /// Calculate the sha256 of `bytes`, the hash function of blocks and merkle trees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let digest = Hash256::digest(b"lease agreement");
+    /// assert_eq!(digest, Hash256::digest(b"lease agreement"));
+    /// assert_ne!(digest, Hash256::digest(b"lease agreement 2"));
+    /// assert_eq!(Hash256::digest(b"").to_hex(), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    /// ```
+    pub fn digest(bytes: &[u8]) -> Hash256 {
+        Hash256(sha256(bytes))
+    }
+
+    /// Return the bytes of the hash.
+    pub fn as_bytes(&self) -> &BlockHash {
+        &self.0
+    }
+
+    /// Encode the hash as 64 lowercase hexadecimal digits.
+    pub fn to_hex(&self) -> String {
+        hex::to_hex(&self.0)
+    }
+}
+
+impl From<BlockHash> for Hash256 {
+    fn from(bytes: BlockHash) -> Hash256 {
+        Hash256(bytes)
+    }
+}
+
+impl From<Hash256> for BlockHash {
+    fn from(hash: Hash256) -> BlockHash {
+        hash.0
+    }
+}
+
+impl AsRef<[u8]> for Hash256 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PartialEq for Hash256 {
+    fn eq(&self, other: &Hash256) -> bool {
+        self == &other.0
+    }
+}
+
+impl PartialEq<BlockHash> for Hash256 {
+    fn eq(&self, other: &BlockHash) -> bool {
+        let difference = self.0.iter().zip(other.iter()).fold(0, |difference, (a, b)| difference | (a ^ b));
+        std::hint::black_box(difference) == 0
+    }
+}
+
+impl std::hash::Hash for Hash256 {
+    fn hash<S: std::hash::Hasher>(&self, state: &mut S) {
+        self.0.hash(state);
+    }
+}
+
+impl fmt::Display for Hash256 {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.to_hex())
+    }
+}
+
+impl fmt::Debug for Hash256 {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Hash256({})", self.to_hex())
+    }
+}
+
+/// Errors returned when parsing a [`Hash256`] from hexadecimal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseHashError {
+    /// The text does not have 64 characters, holding its length.
+    BadLength(usize),
+    /// The text contains a character that is not a hexadecimal digit.
+    BadDigit,
+}
+
+impl fmt::Display for ParseHashError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseHashError::BadLength(len) => write!(formatter, "hash of {} characters instead of {}", len, 2 * BLOCKHASHLEN),
+            ParseHashError::BadDigit => write!(formatter, "hash contains a non hexadecimal character"),
+        }
+    }
+}
+
+impl std::error::Error for ParseHashError {}
+
+impl FromStr for Hash256 {
+    type Err = ParseHashError;
+
+    /// Parse 64 hexadecimal digits of either case.
+    fn from_str(text: &str) -> Result<Hash256, ParseHashError> {
+        if text.len() != 2 * BLOCKHASHLEN { return Err(ParseHashError::BadLength(text.len())); }
+        let bytes = hex::from_hex(text).ok_or(ParseHashError::BadDigit)?;
+        let mut hash : BlockHash = [0; BLOCKHASHLEN];
+        hash.copy_from_slice(&bytes);
+        Ok(Hash256(hash))
+    }
+}
+
+impl Byteable for Hash256 {
+    fn bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl FixedByteable for Hash256 {
+    const LEN: usize = BLOCKHASHLEN;
+    fn write(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.0);
+    }
+}
+
+impl FromBytes for Hash256 {
+    fn from_bytes(bytes: &[u8]) -> Option<Hash256> {
+        BlockHash::from_bytes(bytes).map(Hash256)
+    }
+}
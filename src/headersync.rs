@@ -0,0 +1,268 @@
+use std::fmt;
+use super::*;
+use crate::varint;
+
+/// Magic bytes opening every header stream.
+const MAGIC: [u8; 3] = *b"BHS";
+/// Revision of the header stream format: 2 added the height, 3 the target, 4 the chain identifier.
+const FORMAT: u8 = 4;
+
+/// The entry has no previous hash (first block of a chain).
+const FLAG_NO_PREV: u8 = 0b0000_0001;
+/// The previous hash equals the hash of the preceding header and is omitted.
+const FLAG_LINKED: u8 = 0b0000_0010;
+/// The version differs from the preceding entry and follows the flags byte.
+const FLAG_VERSION: u8 = 0b0000_0100;
+/// The height is not the one following the preceding entry and is sent as a varint.
+const FLAG_HEIGHT: u8 = 0b0000_1000;
+/// The target differs from the preceding entry and follows the height.
+const FLAG_TARGET: u8 = 0b0001_0000;
+/// The chain identifier differs from the preceding entry and follows the target.
+const FLAG_CHAIN: u8 = 0b0010_0000;
+const KNOWN_FLAGS: u8 = FLAG_NO_PREV | FLAG_LINKED | FLAG_VERSION | FLAG_HEIGHT | FLAG_TARGET | FLAG_CHAIN;
+
+/// Errors returned when decoding a header stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderSyncError {
+    /// The stream does not start with the expected magic bytes.
+    BadMagic,
+    /// The stream uses a format revision this version does not understand.
+    UnsupportedFormat(u8),
+    /// The stream ended in the middle of an entry.
+    Truncated,
+    /// An entry uses flags or values that can not be produced by the encoder.
+    Malformed,
+    /// Bytes remain after the announced number of entries.
+    TrailingBytes,
+}
+
+impl fmt::Display for HeaderSyncError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeaderSyncError::BadMagic => write!(formatter, "not a header stream"),
+            HeaderSyncError::UnsupportedFormat(format) => write!(formatter, "unsupported header stream format {}", format),
+            HeaderSyncError::Truncated => write!(formatter, "header stream is truncated"),
+            HeaderSyncError::Malformed => write!(formatter, "header stream is malformed"),
+            HeaderSyncError::TrailingBytes => write!(formatter, "trailing bytes after header stream"),
+        }
+    }
+}
+
+impl std::error::Error for HeaderSyncError {}
+
+/// Encode a sequence of headers in the compact header stream format.
+///
+/// # Description
+///
+/// * `headers` - Headers in chain order, oldest first.
+///
+/// The stream starts with the magic `BHS`, the format revision and the
+/// number of entries as a varint. Block hashes are not transmitted since they are
+/// recalculated from the headers. Each entry is delta-encoded against the
+/// preceding one:
+///
+/// * a flags byte, followed by the version only when it changed
+///   (the first entry is compared against [`VERSION`]);
+/// * the previous hash, omitted when absent or equal to the hash of the
+///   preceding header;
+/// * the merkle root;
+/// * the timestamp as a zigzag varint delta from the preceding timestamp;
+/// * the nonce as a varint;
+/// * the height as a varint, only when it is not the height of the preceding
+///   entry plus one (the first entry is compared against `0`);
+/// * the target, only when it changed (the first entry is compared against
+///   [`MAX_TARGET`]);
+/// * the chain identifier as a varint, only when it changed (the first entry is
+///   compared against `0`).
+///
+/// The encoding is deterministic: the same headers always produce the same bytes.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 2] = [1, 2];
+/// let first : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 1524885322, 0);
+/// let second : BlockchainBlock<i32> = BlockchainBlock::builder()
+///     .parent(first.header())
+///     .timestamp(1524885382)
+///     .nonce(1)
+///     .data(&data)
+///     .build();
+/// let headers = vec![*first.header(), *second.header()];
+///
+/// let stream = encode_headers(&headers);
+/// assert!(stream.len() < 2 * (BLOCKHASHLEN + 8));
+/// assert_eq!(decode_headers(&stream).unwrap(), headers);
+/// ```
+pub fn encode_headers(headers: &[BlockHeader]) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::with_capacity(8 + headers.len() * (BLOCKHASHLEN + 8));
+    out.extend_from_slice(&MAGIC);
+    out.push(FORMAT);
+    varint::write_u64(&mut out, headers.len() as u64);
+
+    let mut last_hash: Option<Hash256> = None;
+    let mut last_timestamp: u64 = 0;
+    let mut last_version: u8 = VERSION;
+    let mut next_height: u64 = 0;
+    let mut last_target: BlockHash = MAX_TARGET;
+    let mut last_chain_id: u32 = 0;
+    for header in headers {
+        let mut flags = 0;
+        match header.prev_hash {
+            None => flags |= FLAG_NO_PREV,
+            Some(prev) if Some(prev) == last_hash => flags |= FLAG_LINKED,
+            Some(_) => (),
+        }
+        if header.version != last_version { flags |= FLAG_VERSION; }
+        if header.height != next_height { flags |= FLAG_HEIGHT; }
+        if header.target != last_target { flags |= FLAG_TARGET; }
+        if header.chain_id != last_chain_id { flags |= FLAG_CHAIN; }
+        out.push(flags);
+        if flags & FLAG_VERSION != 0 { out.push(header.version); }
+        if flags & (FLAG_NO_PREV | FLAG_LINKED) == 0 {
+            if let Some(prev) = &header.prev_hash { out.extend_from_slice(prev.as_bytes()); }
+        }
+        out.extend_from_slice(header.merkle_root.as_bytes());
+        varint::write_u64(&mut out, varint::zigzag(header.timestamp.wrapping_sub(last_timestamp) as i64));
+        varint::write_u64(&mut out, header.nonce);
+        if flags & FLAG_HEIGHT != 0 { varint::write_u64(&mut out, header.height); }
+        if flags & FLAG_TARGET != 0 { out.extend_from_slice(&header.target); }
+        if flags & FLAG_CHAIN != 0 { varint::write_u64(&mut out, u64::from(header.chain_id)); }
+
+        last_hash = Some(header.hash());
+        last_timestamp = header.timestamp;
+        last_version = header.version;
+        next_height = header.height.wrapping_add(1);
+        last_target = header.target;
+        last_chain_id = header.chain_id;
+    }
+    out
+}
+
+fn read_hash(bytes: &[u8], pos: &mut usize, into: &mut [u8]) -> Result<(), HeaderSyncError> {
+    let end = *pos + into.len();
+    if end > bytes.len() { return Err(HeaderSyncError::Truncated); }
+    into.copy_from_slice(&bytes[*pos..end]);
+    *pos = end;
+    Ok(())
+}
+
+fn read_byte(bytes: &[u8], pos: &mut usize) -> Result<u8, HeaderSyncError> {
+    let byte = *bytes.get(*pos).ok_or(HeaderSyncError::Truncated)?;
+    *pos += 1;
+    Ok(byte)
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, HeaderSyncError> {
+    let start = *pos;
+    varint::read_u64(bytes, pos).ok_or_else(|| {
+        // running out of input with the continuation bit still set is a truncation
+        let unterminated = *pos >= bytes.len() && bytes[start..].iter().all(|b| b & 0x80 != 0);
+        if unterminated { HeaderSyncError::Truncated } else { HeaderSyncError::Malformed }
+    })
+}
+
+/// Decode a header stream produced by [`encode_headers`].
+///
+/// # Description
+///
+/// * `bytes` - The complete header stream.
+///
+/// Only canonical streams are accepted: any encoding the encoder would not
+/// produce for the decoded headers is rejected with [`HeaderSyncError::Malformed`].
+/// Streams of an older revision lack fields of the current header and are rejected
+/// with [`HeaderSyncError::UnsupportedFormat`].
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 1] = [1];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 1524885322, 0);
+/// let mut stream = encode_headers(&[*block.header()]);
+/// stream[3] = 3;
+/// assert_eq!(decode_headers(&stream), Err(HeaderSyncError::UnsupportedFormat(3)));
+/// ```
+pub fn decode_headers(bytes: &[u8]) -> Result<Vec<BlockHeader>, HeaderSyncError> {
+    if bytes.len() < MAGIC.len() + 1 { return Err(HeaderSyncError::Truncated); }
+    if bytes[..MAGIC.len()] != MAGIC { return Err(HeaderSyncError::BadMagic); }
+    if bytes[MAGIC.len()] != FORMAT { return Err(HeaderSyncError::UnsupportedFormat(bytes[MAGIC.len()])); }
+    let mut pos = MAGIC.len() + 1;
+    let count = read_varint(bytes, &mut pos)?;
+
+    // every entry takes at least flags, merkle root, timestamp and nonce
+    let min_entry = 3 + BLOCKHASHLEN;
+    if count > (bytes.len() / min_entry) as u64 { return Err(HeaderSyncError::Truncated); }
+
+    let mut headers: Vec<BlockHeader> = Vec::with_capacity(count as usize);
+    let mut last_hash: Option<Hash256> = None;
+    let mut last_timestamp: u64 = 0;
+    let mut last_version: u8 = VERSION;
+    let mut next_height: u64 = 0;
+    let mut last_target: BlockHash = MAX_TARGET;
+    let mut last_chain_id: u32 = 0;
+    for _ in 0..count {
+        let flags = read_byte(bytes, &mut pos)?;
+        if flags & !KNOWN_FLAGS != 0 || flags & FLAG_NO_PREV != 0 && flags & FLAG_LINKED != 0 {
+            return Err(HeaderSyncError::Malformed);
+        }
+        let version = if flags & FLAG_VERSION != 0 {
+            let version = read_byte(bytes, &mut pos)?;
+            if version == last_version { return Err(HeaderSyncError::Malformed); }
+            version
+        } else { last_version };
+
+        let prev_hash = if flags & FLAG_NO_PREV != 0 {
+            None
+        } else if flags & FLAG_LINKED != 0 {
+            Some(last_hash.ok_or(HeaderSyncError::Malformed)?)
+        } else {
+            let mut prev: BlockHash = [0; BLOCKHASHLEN];
+            read_hash(bytes, &mut pos, &mut prev)?;
+            let prev = Hash256::new(prev);
+            if Some(prev) == last_hash { return Err(HeaderSyncError::Malformed); }
+            Some(prev)
+        };
+
+        let mut merkle_root: BlockHash = [0; BLOCKHASHLEN];
+        read_hash(bytes, &mut pos, &mut merkle_root)?;
+        let merkle_root = Hash256::new(merkle_root);
+        let delta = varint::unzigzag(read_varint(bytes, &mut pos)?);
+        let timestamp = last_timestamp.wrapping_add(delta as u64);
+        let nonce = read_varint(bytes, &mut pos)?;
+        let height = if flags & FLAG_HEIGHT != 0 {
+            let height = read_varint(bytes, &mut pos)?;
+            if height == next_height { return Err(HeaderSyncError::Malformed); }
+            height
+        } else { next_height };
+        let target = if flags & FLAG_TARGET != 0 {
+            let mut target: BlockHash = [0; BLOCKHASHLEN];
+            read_hash(bytes, &mut pos, &mut target)?;
+            if target == last_target { return Err(HeaderSyncError::Malformed); }
+            target
+        } else { last_target };
+        let chain_id = if flags & FLAG_CHAIN != 0 {
+            let chain_id = read_varint(bytes, &mut pos)?;
+            if chain_id > u64::from(u32::MAX) || chain_id == u64::from(last_chain_id) {
+                return Err(HeaderSyncError::Malformed);
+            }
+            chain_id as u32
+        } else { last_chain_id };
+
+        let header = BlockHeader { prev_hash, height, merkle_root, timestamp, nonce, version, target, chain_id, extension: () };
+        last_hash = Some(header.hash());
+        last_timestamp = timestamp;
+        last_version = version;
+        next_height = height.wrapping_add(1);
+        last_target = target;
+        last_chain_id = chain_id;
+        headers.push(header);
+    }
+    if pos != bytes.len() { return Err(HeaderSyncError::TrailingBytes); }
+    Ok(headers)
+}
@@ -0,0 +1,151 @@
+//! Merkle inclusion proofs of the items of a block.
+//!
+//! The proof follows the merkle tree of the block data: the tree is split in halves
+//! down to one or two items, which are hashed together as raw bytes. The proof holds
+//! the bytes of the item hashed with the proven one and the roots of the halves not
+//! containing it, so it grows with the logarithm of the number of items.
+
+use super::*;
+use crate::merkle;
+
+/// Proof that an item is part of the data committed to by a merkle root.
+///
+/// # Description
+///
+/// The tree hashes items and inner nodes alike, so two 32-byte items hashed together
+/// can not be told apart from two child roots: with such items, check `count` against
+/// a trusted source.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : Vec<String> = (0..5).map(|index| format!("item {}", index)).collect();
+/// let block : BlockchainBlock<String> = BlockchainBlock::new(None, &data, 4, 3);
+/// for (position, item) in data.iter().enumerate() {
+///     let proof = block.inclusion_proof(position).unwrap();
+///     assert!(proof.verify(item, &block.merkle_root()));
+///     assert!(verify::inclusion_proof(&proof, item, block.header()));
+/// }
+///
+/// let proof = block.inclusion_proof(3).unwrap();
+/// assert_eq!(proof.path.len(), 2);
+/// assert!(!proof.verify(&data[2], &block.merkle_root()));
+/// assert!(!InclusionProof { position: 2, ..proof.clone() }.verify(&data[3], &block.merkle_root()));
+/// assert!(block.inclusion_proof(5).is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    /// position of the item in the block data
+    pub position: u64,
+    /// number of items in the block data
+    pub count: u64,
+    /// bytes of the item hashed together with the proven one, empty when the item is
+    /// hashed with itself
+    pub paired: Vec<u8>,
+    /// roots of the halves not containing the item, from the leaf up to the root
+    pub path: Vec<BlockHash>,
+}
+
+impl InclusionProof {
+    /// Check `item` is at `position` among the `count` items committed to by `merkle_root`.
+    pub fn verify<T: Byteable>(&self, item: &T, merkle_root: &Hash256) -> bool {
+        if self.position >= self.count { return false; }
+
+        // replay the descent from the root to find on which side the item lies
+        let mut left_sides : Vec<bool> = Vec::new();
+        let (mut offset, mut size) = (0, self.count);
+        while size > 2 {
+            let half = size / 2;
+            let left = self.position - offset < half;
+            left_sides.push(left);
+            if left { size = half; } else { offset += half; size -= half; }
+        }
+        if left_sides.len() != self.path.len() { return false; }
+
+        let item = item.bytes();
+        let leaf = match (size, self.position - offset) {
+            (1, _) if self.paired.is_empty() => [&item[..], &item[..]].concat(),
+            (1, _) => return false,
+            (_, 0) => [&item[..], &self.paired[..]].concat(),
+            _ => [&self.paired[..], &item[..]].concat(),
+        };
+        let mut hash = sha256(&leaf);
+        for (left, sibling) in left_sides.iter().rev().zip(&self.path) {
+            hash = if *left { merkle::merkle_hash(&hash, sibling) } else { merkle::merkle_hash(sibling, &hash) };
+        }
+        *merkle_root == hash
+    }
+}
+
+impl<'a, T, H> BlockchainBlock<'a, T, H>
+where
+    T: Byteable + Clone
+{
+    /// Build the proof that the item at `position` is part of the block data.
+    /// Returns `None` when there is no item at `position`.
+    pub fn inclusion_proof(&self, position: usize) -> Option<InclusionProof> {
+        prove(self.data(), position)
+    }
+}
+
+/// Build the proof that `all[position]` is part of the items committed to by the merkle
+/// root of `all`.
+pub(crate) fn prove<T: Byteable>(all: &[T], position: usize) -> Option<InclusionProof> {
+    let mut items = all;
+    if position >= items.len() { return None; }
+    let mut index = position;
+    let mut path : Vec<BlockHash> = Vec::new();
+    while items.len() > 2 {
+        let (left, right) = items.split_at(items.len() / 2);
+        if index < left.len() {
+            path.push(merkle::merkle_root(right));
+            items = left;
+        } else {
+            path.push(merkle::merkle_root(left));
+            index -= left.len();
+            items = right;
+        }
+    }
+    path.reverse();
+    let paired = if items.len() == 2 { items[1 - index].bytes() } else { Vec::new() };
+    Some(InclusionProof { position: position as u64, count: all.len() as u64, paired, path })
+}
+
+/// Build the proofs that the items of `all` at `positions`, increasing, are part of the
+/// items committed to by the merkle root of `all`, hashing each node of the tree once.
+/// Returns `None` when a position has no item.
+pub(crate) fn prove_positions<T: Byteable>(all: &[T], positions: &[usize]) -> Option<Vec<InclusionProof>> {
+    if positions.iter().any(|position| *position >= all.len()) { return None; }
+    let mut proofs : Vec<InclusionProof> = positions.iter().map(|position| InclusionProof {
+        position: *position as u64,
+        count: all.len() as u64,
+        paired: Vec::new(),
+        path: Vec::new(),
+    }).collect();
+    if !proofs.is_empty() { prove_node(all, 0, &mut proofs); }
+    Some(proofs)
+}
+
+/// Fill the proofs of `proofs`, all of items of `items` starting at position `offset`,
+/// from the leaf up to the root of `items`, and return that root.
+fn prove_node<T: Byteable>(items: &[T], offset: u64, proofs: &mut [InclusionProof]) -> BlockHash {
+    if items.len() <= 2 {
+        for proof in proofs.iter_mut() {
+            let index = (proof.position - offset) as usize;
+            if items.len() == 2 { proof.paired = items[1 - index].bytes(); }
+        }
+        return merkle::merkle_root(items);
+    }
+    let (left, right) = items.split_at(items.len() / 2);
+    let middle = offset + left.len() as u64;
+    let split = proofs.iter().take_while(|proof| proof.position < middle).count();
+    let (left_proofs, right_proofs) = proofs.split_at_mut(split);
+    let left_root = if left_proofs.is_empty() { merkle::merkle_root(left) } else { prove_node(left, offset, left_proofs) };
+    let right_root = if right_proofs.is_empty() { merkle::merkle_root(right) } else { prove_node(right, middle, right_proofs) };
+    for proof in left_proofs.iter_mut() { proof.path.push(right_root); }
+    for proof in right_proofs.iter_mut() { proof.path.push(left_root); }
+    merkle::merkle_hash(&left_root, &right_root)
+}
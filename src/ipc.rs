@@ -0,0 +1,240 @@
+//! Local access to a [`Blockchain`] over a Unix domain socket.
+//!
+//! Processes of the same host, such as a sidecar next to a node, query and extend the
+//! chain of an [`IpcServer`] with an [`IpcClient`]. Requests and responses are frames
+//! made of a 1-byte code, the length of the payload as a 4-byte little endian integer
+//! and the payload:
+//!
+//! * `1` tip, empty payload;
+//! * `2` block by hash, the 32-byte hash;
+//! * `3` block by height, the height as an 8-byte little endian integer;
+//! * `4` push, the block encoded by [`to_bytes`](BlockchainBlock::to_bytes).
+//!
+//! The server answers each request with `128` and the encoded block, empty for a push,
+//! `129` when there is no such block, or `130` and the error message in UTF-8. Frames
+//! larger than `max_block_bytes` plus one kilobyte end the connection.
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use super::*;
+
+const TIP: u8 = 1;
+const BLOCK: u8 = 2;
+const AT_HEIGHT: u8 = 3;
+const PUSH: u8 = 4;
+const OK: u8 = 128;
+const NOT_FOUND: u8 = 129;
+const FAILED: u8 = 130;
+
+/// Room for the code and length of a frame header and the request fields.
+const FRAME_OVERHEAD: usize = 1 << 10;
+
+/// Time a connection may stay idle before the server closes it.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn write_frame(stream: &mut UnixStream, code: u8, payload: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(payload.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))?;
+    let mut frame = Vec::with_capacity(5 + payload.len());
+    frame.push(code);
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame)
+}
+
+fn read_frame(stream: &mut UnixStream, max_len: usize) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0; 5];
+    stream.read_exact(&mut header)?;
+    let len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if len > max_len { return Err(io::Error::new(io::ErrorKind::InvalidData, "frame too large")); }
+    let mut payload = vec![0; len];
+    stream.read_exact(&mut payload)?;
+    Ok((header[0], payload))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Server giving local processes access to a shared [`Blockchain`].
+///
+/// # Description
+///
+/// [`serve_next`](IpcServer::serve_next) answers the requests of one connection until
+/// the client closes it or stays idle for 30 seconds. Run it in a loop to serve clients
+/// one after the other, or from several threads to serve them concurrently: the chain
+/// is locked for the time of each request only.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+/// use std::sync::{Arc, Mutex};
+///
+/// let path = std::env::temp_dir().join(format!("blockchainblock-{}.sock", std::process::id()));
+/// let _ = std::fs::remove_file(&path);
+/// let chain : Arc<Mutex<Blockchain<String>>> = Arc::new(Mutex::new(Blockchain::new()));
+/// let server = IpcServer::bind(&path, chain.clone()).unwrap();
+/// let serving = std::thread::spawn(move || server.serve_next());
+///
+/// let mut client = IpcClient::connect(&path).unwrap();
+/// assert_eq!(client.tip::<String, ()>().unwrap(), None);
+/// let genesis : OwnedBlock<String> = BlockchainBlock::genesis(&ChainParams::default(), &[String::from("genesis")]).into_owned();
+/// client.push(&genesis).unwrap().unwrap();
+/// assert!(client.push(&genesis).unwrap().is_err());
+///
+/// assert_eq!(client.tip().unwrap(), Some(genesis.clone()));
+/// assert_eq!(client.block(&genesis.curr_hash()).unwrap(), Some(genesis.clone()));
+/// assert_eq!(client.at_height::<String, ()>(1).unwrap(), None);
+/// drop(client);
+///
+/// serving.join().unwrap().unwrap();
+/// assert_eq!(chain.lock().unwrap().len(), 1);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct IpcServer<T: Clone + 'static, H = ()> {
+    listener: UnixListener,
+    chain: Arc<Mutex<Blockchain<T, H>>>,
+}
+
+impl<T, H> IpcServer<T, H>
+where
+    T: FromBytes + Byteable + Clone + 'static,
+    H: FromBytes + Byteable
+{
+    /// Listen on the socket at `path`, serving `chain`.
+    pub fn bind<P: AsRef<Path>>(path: P, chain: Arc<Mutex<Blockchain<T, H>>>) -> io::Result<IpcServer<T, H>> {
+        Ok(IpcServer { listener: UnixListener::bind(path)?, chain })
+    }
+
+    /// Accept a connection and answer its requests until it is closed.
+    pub fn serve_next(&self) -> io::Result<()> {
+        let (mut stream, _) = self.listener.accept()?;
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        loop {
+            let max_len = self.lock()?.params().max_block_bytes.saturating_add(FRAME_OVERHEAD);
+            let (code, payload) = match read_frame(&mut stream, max_len) {
+                Ok(frame) => frame,
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(error) => return Err(error),
+            };
+            let (code, payload) = self.answer(code, &payload)?;
+            write_frame(&mut stream, code, &payload)?;
+        }
+    }
+
+    fn lock(&self) -> io::Result<std::sync::MutexGuard<'_, Blockchain<T, H>>> {
+        self.chain.lock().map_err(|_| io::Error::other("chain lock poisoned"))
+    }
+
+    fn answer(&self, code: u8, payload: &[u8]) -> io::Result<(u8, Vec<u8>)> {
+        let mut chain = self.lock()?;
+        let found = match code {
+            TIP if payload.is_empty() => chain.tip(),
+            BLOCK => match Hash256::from_bytes(payload) {
+                Some(hash) => chain.block(&hash),
+                None => return Ok((FAILED, b"bad block hash".to_vec())),
+            },
+            AT_HEIGHT => match <[u8; 8]>::try_from(payload) {
+                Ok(height) => chain.at_height(u64::from_le_bytes(height)),
+                Err(_) => return Ok((FAILED, b"bad block height".to_vec())),
+            },
+            PUSH => {
+                let max_block_bytes = chain.params().max_block_bytes;
+                let pushed = BlockchainBlock::from_bytes_limited(payload, max_block_bytes)
+                    .and_then(BlockchainBlock::seal)
+                    .map_err(|error| error.to_string())
+                    .and_then(|block| chain.push(block).map_err(|error| error.to_string()));
+                return Ok(match pushed {
+                    Ok(_) => (OK, Vec::new()),
+                    Err(message) => (FAILED, message.into_bytes()),
+                });
+            },
+            _ => return Ok((FAILED, b"unknown request".to_vec())),
+        };
+        Ok(match found {
+            Some(block) => (OK, block.to_bytes()),
+            None => (NOT_FOUND, Vec::new()),
+        })
+    }
+}
+
+/// Client of an [`IpcServer`].
+///
+/// Blocks larger than [`DEFAULT_MAX_BLOCK_BYTES`] are rejected, see
+/// [`set_max_block_bytes`](IpcClient::set_max_block_bytes) for chains with other limits.
+pub struct IpcClient {
+    stream: UnixStream,
+    max_block_bytes: usize,
+}
+
+impl IpcClient {
+    /// Connect to the server listening on the socket at `path`.
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<IpcClient> {
+        Ok(IpcClient { stream: UnixStream::connect(path)?, max_block_bytes: DEFAULT_MAX_BLOCK_BYTES })
+    }
+
+    /// Reject the blocks received larger than `max_block_bytes`.
+    pub fn set_max_block_bytes(&mut self, max_block_bytes: usize) {
+        self.max_block_bytes = max_block_bytes;
+    }
+
+    /// Return the tip of the chain.
+    pub fn tip<T, H>(&mut self) -> io::Result<Option<OwnedBlock<T, H>>>
+    where
+        T: FromBytes + Byteable + Clone,
+        H: FromBytes + Byteable
+    {
+        self.fetch(TIP, &[])
+    }
+
+    /// Find the block with hash `hash` in the chain or in a side branch.
+    pub fn block<T, H>(&mut self, hash: &Hash256) -> io::Result<Option<OwnedBlock<T, H>>>
+    where
+        T: FromBytes + Byteable + Clone,
+        H: FromBytes + Byteable
+    {
+        self.fetch(BLOCK, hash.as_bytes())
+    }
+
+    /// Return the block of the chain at height `height`.
+    pub fn at_height<T, H>(&mut self, height: u64) -> io::Result<Option<OwnedBlock<T, H>>>
+    where
+        T: FromBytes + Byteable + Clone,
+        H: FromBytes + Byteable
+    {
+        self.fetch(AT_HEIGHT, &height.to_le_bytes())
+    }
+
+    /// Add `block` to the chain, returning the message of the server when it rejects it.
+    pub fn push<T, H>(&mut self, block: &BlockchainBlock<T, H>) -> io::Result<Result<(), String>>
+    where
+        T: Byteable + Clone,
+        H: Byteable
+    {
+        write_frame(&mut self.stream, PUSH, &block.to_bytes())?;
+        match read_frame(&mut self.stream, FRAME_OVERHEAD)? {
+            (OK, _) => Ok(Ok(())),
+            (FAILED, message) => Ok(Err(String::from_utf8_lossy(&message).into_owned())),
+            _ => Err(invalid("unexpected response")),
+        }
+    }
+
+    fn fetch<T, H>(&mut self, code: u8, payload: &[u8]) -> io::Result<Option<OwnedBlock<T, H>>>
+    where
+        T: FromBytes + Byteable + Clone,
+        H: FromBytes + Byteable
+    {
+        write_frame(&mut self.stream, code, payload)?;
+        match read_frame(&mut self.stream, self.max_block_bytes.saturating_add(FRAME_OVERHEAD))? {
+            (OK, bytes) => BlockchainBlock::from_bytes_limited(&bytes, self.max_block_bytes).map(Some).map_err(|error| invalid(&error.to_string())),
+            (NOT_FOUND, _) => Ok(None),
+            (FAILED, message) => Err(io::Error::other(String::from_utf8_lossy(&message).into_owned())),
+            _ => Err(invalid("unexpected response")),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+use super::*;
+use crate::json::{self, JsonError};
+
+/// JSON payload canonicalized per RFC 8785 (JSON Canonicalization Scheme).
+///
+/// Semantically equal JSON texts produced by different serializers (member
+/// order, whitespace, escapes, number notation) canonicalize to the same
+/// bytes, so they commit to identical leaves.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CanonicalJson {
+    canonical: String,
+}
+
+impl CanonicalJson {
+    /// Parse `text` and build its canonical form.
+    ///
+    /// # Description
+    ///
+    /// * `text` - JSON text. Duplicate object keys, lone surrogates and numbers
+    ///   out of the range of a double are rejected, as I-JSON requires.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let compact = CanonicalJson::parse("{\"b\":[1e0,true],\"a\":\"\\u0041\"}").unwrap();
+    /// let pretty = CanonicalJson::parse("{\n  \"a\": \"A\",\n  \"b\": [ 1.0, true ]\n}").unwrap();
+    /// assert_eq!(compact.as_str(), "{\"a\":\"A\",\"b\":[1,true]}");
+    ///
+    /// let data = [compact];
+    /// let other = [pretty];
+    /// let block : BlockchainBlock<CanonicalJson> = BlockchainBlock::new(None, &data, 4, 3);
+    /// assert!(block.check_value_inblock(&other[0], 0));
+    /// ```
+    pub fn parse(text: &str) -> Result<CanonicalJson, JsonError> {
+        let value = json::parse(text)?;
+        let mut canonical = String::with_capacity(text.len());
+        json::write_canonical(&mut canonical, &value)?;
+        Ok(CanonicalJson { canonical })
+    }
+
+    /// Canonical JSON text.
+    pub fn as_str(&self) -> &str {
+        &self.canonical
+    }
+}
+
+impl std::str::FromStr for CanonicalJson {
+    type Err = JsonError;
+
+    fn from_str(text: &str) -> Result<CanonicalJson, JsonError> {
+        CanonicalJson::parse(text)
+    }
+}
+
+impl fmt::Display for CanonicalJson {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.canonical)
+    }
+}
+
+impl Byteable for CanonicalJson {
+    fn bytes(&self) -> Vec<u8> {
+        self.canonical.as_bytes().to_vec()
+    }
+}
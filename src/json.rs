@@ -0,0 +1,321 @@
+//! Minimal JSON reader and writer used by the JSON based payload and export formats.
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// Nesting limit protecting the recursive parser from hostile input.
+const MAX_DEPTH: usize = 128;
+
+/// Parsed JSON value. Numbers keep their source text so no precision is lost
+/// before a caller decides how to interpret them.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Errors returned when reading JSON text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonError {
+    /// The text ended before the value was complete.
+    UnexpectedEnd,
+    /// An unexpected character was found at the given byte offset.
+    UnexpectedChar(usize),
+    /// The number starting at the given byte offset is invalid or out of range.
+    InvalidNumber(usize),
+    /// The string starting at the given byte offset contains an invalid escape
+    /// or an unpaired surrogate.
+    InvalidString(usize),
+    /// An object contains the same key twice.
+    DuplicateKey(String),
+    /// The value is nested deeper than the parser allows.
+    TooDeep,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::UnexpectedEnd => write!(formatter, "unexpected end of JSON text"),
+            JsonError::UnexpectedChar(at) => write!(formatter, "unexpected character at offset {}", at),
+            JsonError::InvalidNumber(at) => write!(formatter, "invalid number at offset {}", at),
+            JsonError::InvalidString(at) => write!(formatter, "invalid string at offset {}", at),
+            JsonError::DuplicateKey(key) => write!(formatter, "duplicate key {:?}", key),
+            JsonError::TooDeep => write!(formatter, "JSON nested too deeply"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+/// Parse a complete JSON text. Duplicate object keys are rejected.
+pub(crate) fn parse(text: &str) -> Result<Value, JsonError> {
+    let mut parser = Parser { text: text.as_bytes(), pos: 0 };
+    let value = parser.value(0)?;
+    parser.whitespace();
+    if parser.pos != parser.text.len() { return Err(JsonError::UnexpectedChar(parser.pos)); }
+    Ok(value)
+}
+
+impl<'a> Parser<'a> {
+    fn whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.text.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Result<u8, JsonError> {
+        self.text.get(self.pos).copied().ok_or(JsonError::UnexpectedEnd)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonError> {
+        if self.peek()? != byte { return Err(JsonError::UnexpectedChar(self.pos)); }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &'static str, value: Value) -> Result<Value, JsonError> {
+        let end = self.pos + word.len();
+        if end > self.text.len() { return Err(JsonError::UnexpectedEnd); }
+        if &self.text[self.pos..end] != word.as_bytes() { return Err(JsonError::UnexpectedChar(self.pos)); }
+        self.pos = end;
+        Ok(value)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, JsonError> {
+        if depth > MAX_DEPTH { return Err(JsonError::TooDeep); }
+        self.whitespace();
+        match self.peek()? {
+            b'n' => self.literal("null", Value::Null),
+            b't' => self.literal("true", Value::Bool(true)),
+            b'f' => self.literal("false", Value::Bool(false)),
+            b'"' => Ok(Value::String(self.string()?)),
+            b'[' => {
+                self.pos += 1;
+                let mut items : Vec<Value> = Vec::new();
+                self.whitespace();
+                if self.peek()? == b']' { self.pos += 1; return Ok(Value::Array(items)); }
+                loop {
+                    items.push(self.value(depth + 1)?);
+                    self.whitespace();
+                    match self.peek()? {
+                        b',' => self.pos += 1,
+                        b']' => { self.pos += 1; return Ok(Value::Array(items)); },
+                        _ => return Err(JsonError::UnexpectedChar(self.pos)),
+                    }
+                }
+            },
+            b'{' => {
+                self.pos += 1;
+                let mut members : Vec<(String, Value)> = Vec::new();
+                let mut keys : HashSet<String> = HashSet::new();
+                self.whitespace();
+                if self.peek()? == b'}' { self.pos += 1; return Ok(Value::Object(members)); }
+                loop {
+                    self.whitespace();
+                    if self.peek()? != b'"' { return Err(JsonError::UnexpectedChar(self.pos)); }
+                    let key = self.string()?;
+                    if !keys.insert(key.clone()) { return Err(JsonError::DuplicateKey(key)); }
+                    self.whitespace();
+                    self.expect(b':')?;
+                    let value = self.value(depth + 1)?;
+                    members.push((key, value));
+                    self.whitespace();
+                    match self.peek()? {
+                        b',' => self.pos += 1,
+                        b'}' => { self.pos += 1; return Ok(Value::Object(members)); },
+                        _ => return Err(JsonError::UnexpectedChar(self.pos)),
+                    }
+                }
+            },
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => Err(JsonError::UnexpectedChar(self.pos)),
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.text.get(self.pos) { self.pos += 1; }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<Value, JsonError> {
+        let start = self.pos;
+        if self.text[self.pos] == b'-' { self.pos += 1; }
+        match self.text.get(self.pos) {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => { self.digits(); },
+            _ => return Err(JsonError::InvalidNumber(start)),
+        }
+        if let Some(b'.') = self.text.get(self.pos) {
+            self.pos += 1;
+            if self.digits() == 0 { return Err(JsonError::InvalidNumber(start)); }
+        }
+        if let Some(b'e') | Some(b'E') = self.text.get(self.pos) {
+            self.pos += 1;
+            if let Some(b'+') | Some(b'-') = self.text.get(self.pos) { self.pos += 1; }
+            if self.digits() == 0 { return Err(JsonError::InvalidNumber(start)); }
+        }
+        // only ASCII was consumed, so the slice is valid UTF-8
+        let text = String::from_utf8_lossy(&self.text[start..self.pos]).into_owned();
+        match text.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(Value::Number(text)),
+            _ => Err(JsonError::InvalidNumber(start)),
+        }
+    }
+
+    fn hex4(&mut self, start: usize) -> Result<u32, JsonError> {
+        let end = self.pos + 4;
+        if end > self.text.len() { return Err(JsonError::UnexpectedEnd); }
+        let digits = std::str::from_utf8(&self.text[self.pos..end]).map_err(|_| JsonError::InvalidString(start))?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| JsonError::InvalidString(start))?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) { return Err(JsonError::InvalidString(start)); }
+        self.pos = end;
+        Ok(value)
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        let mut out : Vec<u8> = Vec::new();
+        loop {
+            let byte = self.peek()?;
+            self.pos += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape = self.peek()?;
+                    self.pos += 1;
+                    let decoded = match escape {
+                        b'"' => '"', b'\\' => '\\', b'/' => '/',
+                        b'b' => '\u{8}', b'f' => '\u{c}', b'n' => '\n', b'r' => '\r', b't' => '\t',
+                        b'u' => {
+                            let high = self.hex4(start)?;
+                            let code = if (0xD800..0xDC00).contains(&high) {
+                                if self.text.get(self.pos) != Some(&b'\\') || self.text.get(self.pos + 1) != Some(&b'u') {
+                                    return Err(JsonError::InvalidString(start));
+                                }
+                                self.pos += 2;
+                                let low = self.hex4(start)?;
+                                if !(0xDC00..0xE000).contains(&low) { return Err(JsonError::InvalidString(start)); }
+                                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                            } else { high };
+                            std::char::from_u32(code).ok_or(JsonError::InvalidString(start))?
+                        },
+                        _ => return Err(JsonError::InvalidString(start)),
+                    };
+                    let mut buffer = [0u8; 4];
+                    out.extend_from_slice(decoded.encode_utf8(&mut buffer).as_bytes());
+                },
+                0x00..=0x1f => return Err(JsonError::InvalidString(start)),
+                _ => out.push(byte),
+            }
+        }
+        // the input is a &str and escapes decode to chars, so this never fails
+        String::from_utf8(out).map_err(|_| JsonError::InvalidString(start))
+    }
+}
+
+/// Append `text` as a JSON string literal, escaping only what JSON requires.
+pub(crate) fn write_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Format a finite double the way ECMAScript `Number.prototype.toString` does.
+#[cfg(feature = "jcs")]
+pub(crate) fn format_number(value: f64) -> String {
+    if value == 0.0 { return String::from("0"); }
+    // `{:e}` yields the shortest round-trip digits, e.g. "-1.2345e-7"
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap_or(scientific.len()));
+    let digits : String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent : i32 = exponent[1..].parse().unwrap_or(0);
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut out = String::new();
+    if value < 0.0 { out.push('-'); }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+    out
+}
+
+/// Append the RFC 8785 canonical form of `value`: no insignificant whitespace,
+/// object members sorted by the UTF-16 code units of their keys and numbers
+/// serialized as ECMAScript doubles.
+#[cfg(feature = "jcs")]
+pub(crate) fn write_canonical(out: &mut String, value: &Value) -> Result<(), JsonError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(true) => out.push_str("true"),
+        Value::Bool(false) => out.push_str("false"),
+        Value::Number(text) => {
+            // the parser only accepts numbers within the range of a double
+            let number : f64 = text.parse().map_err(|_| JsonError::InvalidNumber(0))?;
+            out.push_str(&format_number(number));
+        },
+        Value::String(text) => write_string(out, text),
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 { out.push(','); }
+                write_canonical(out, item)?;
+            }
+            out.push(']');
+        },
+        Value::Object(members) => {
+            let mut sorted : Vec<&(String, Value)> = members.iter().collect();
+            sorted.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (index, (key, item)) in sorted.into_iter().enumerate() {
+                if index > 0 { out.push(','); }
+                write_string(out, key);
+                out.push(':');
+                write_canonical(out, item)?;
+            }
+            out.push('}');
+        },
+    }
+    Ok(())
+}
@@ -3,16 +3,119 @@
 #![warn(missing_docs)]
 
 /// Version of the protocol as appearing in block headers.
-pub const VERSION: u8 = 1;
+pub const VERSION: u8 = 3;
 /// Lenght of the Hash block.
 pub const BLOCKHASHLEN : usize = 32; // 2^8 * 2^5
 /// Hash block representation.
 pub type BlockHash = [u8; BLOCKHASHLEN]; // to store SHA256
+/// Merkle root of a block without data: the sha256 of `"blockchainblock empty merkle tree"`.
+/// A tag rather than no bytes, so a block holding a single empty item has a different root.
+pub const EMPTY_MERKLE_ROOT: Hash256 = Hash256::new([
+    0xbb, 0x7a, 0x77, 0xb7, 0x93, 0xbd, 0xd9, 0x6a, 0xc1, 0xb3, 0xf5, 0x04, 0x0d, 0x20, 0x93, 0xee,
+    0xce, 0x5b, 0x0c, 0x23, 0x5e, 0xcc, 0x17, 0x0a, 0xdc, 0x9d, 0xa6, 0x63, 0x28, 0xce, 0x49, 0xde,
+]);
+/// Easiest difficulty target, met by every hash.
+pub const MAX_TARGET: BlockHash = [0xff; BLOCKHASHLEN];
+
+/// Calculate the sha256 of `bytes`.
+pub(crate) fn sha256(bytes: &[u8]) -> BlockHash {
+    let mut result: BlockHash = [0; BLOCKHASHLEN];
+    result.copy_from_slice(&crypto_hash::digest(crypto_hash::Algorithm::SHA256, bytes));
+    result
+}
 
 mod byteable;
-pub use crate::byteable::Byteable;
+pub use crate::byteable::{Byteable, FixedByteable, FromBytes, MAX_FIXED_LEN};
+mod hash256;
+pub use crate::hash256::{Hash256, ParseHashError};
+mod error;
+pub use crate::error::BlockError;
+mod protocolversion;
+pub use crate::protocolversion::{ProtocolVersion, VersionPolicy};
 mod hashable;
 pub use crate::hashable::Hashable;
 // thanks to https://github.com/GeekLaunch/blockchain-rust
 mod blockchainblock;
-pub use crate::blockchainblock::BlockchainBlock;
+pub use crate::blockchainblock::{BlockchainBlock, OwnedBlock};
+mod sealedblock;
+pub use crate::sealedblock::SealedBlock;
+mod blockheader;
+pub use crate::blockheader::BlockHeader;
+mod extensions;
+pub use crate::extensions::{Extension, Extensions};
+mod blockbody;
+pub use crate::blockbody::BlockBody;
+mod clock;
+pub use crate::clock::{Clock, SystemClock, FixedClock};
+mod blockbuilder;
+pub use crate::blockbuilder::BlockchainBlockBuilder;
+mod work;
+pub use crate::work::Work;
+mod chainparams;
+pub use crate::chainparams::{ChainParams, DEFAULT_MAX_BLOCK_BYTES, DEFAULT_MAX_TIME_DRIFT, DEFAULT_MAX_FORK_DEPTH, DEFAULT_MAX_SIDE_BLOCKS,
+    DEFAULT_CONFIRMATIONS_REQUIRED, DEFAULT_MAX_ORPHANS_PER_PEER, DEFAULT_MAX_UNSOLICITED_BYTES};
+mod chainregistry;
+pub use crate::chainregistry::{ChainRegistry, RegistryError};
+mod blockchain;
+pub use crate::blockchain::{Blockchain, Blocks, BlockStatus, ChainError, ReorgEvent, RollbackCost};
+mod orphanpool;
+pub use crate::orphanpool::{OrphanPool, OrphanError};
+mod anchor;
+pub use crate::anchor::{Anchor, AnchorError};
+mod epoch;
+pub use crate::epoch::EpochSummary;
+mod aggregate;
+pub use crate::aggregate::{Aggregate, Noise};
+mod topic;
+pub use crate::topic::{Topic, TopicMessage, Delivery};
+mod query;
+pub use crate::query::{Query, QueryMatch, Queryable, DEFAULT_QUERY_LIMIT};
+mod sample;
+pub use crate::sample::{Audit, Sample};
+#[cfg(unix)]
+mod ipc;
+#[cfg(unix)]
+pub use crate::ipc::{IpcServer, IpcClient};
+mod blockencoding;
+mod blockjson;
+mod blockwriter;
+pub use crate::blockwriter::BlockWriter;
+mod blockpacker;
+pub use crate::blockpacker::BlockPacker;
+mod blocktemplate;
+pub use crate::blocktemplate::BlockTemplate;
+mod compactblock;
+pub use crate::compactblock::{CompactBlock, ShortId, SHORTIDLEN};
+mod merkle;
+mod inclusion;
+pub use crate::inclusion::InclusionProof;
+mod hex;
+mod base64;
+mod varint;
+mod headersync;
+pub use crate::headersync::{HeaderSyncError, encode_headers, decode_headers};
+mod commitreveal;
+pub use crate::commitreveal::{Commitment, Reveal, Salt, SALTLEN};
+mod record;
+pub use crate::record::{Record, RecordCommitment, FieldProof};
+mod delay;
+pub use crate::delay::{DelaySequence, DelayProof};
+pub mod verify;
+mod verifycache;
+pub use crate::verifycache::{VerifyCache, Verified, DEFAULT_VERIFY_CACHE_SIZE};
+pub mod fixtures;
+mod json;
+pub use crate::json::JsonError;
+#[cfg(feature = "jcs")]
+mod jcs;
+#[cfg(feature = "jcs")]
+pub use crate::jcs::CanonicalJson;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "cbor")]
+pub use crate::cbor::{CborValue, CborMap, ToCbor, CborByteable};
+
+/// Runs the examples of `README.md` as doctests.
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+pub struct ReadmeDoctests;
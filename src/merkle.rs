@@ -0,0 +1,78 @@
+//! Merkle tree of the block data.
+//!
+//! One or two items are hashed together as raw bytes (a single item is paired with
+//! itself); larger sets are split in two halves, the left one holding `len / 2` items,
+//! and the roots of both halves are hashed together.
+
+use super::*;
+
+pub(crate) fn merkle_hash(block_left: &BlockHash, block_right: &BlockHash) -> BlockHash {
+    const DOUBLE_BLOCK_LEN : usize = BLOCKHASHLEN * 2;
+    let mut bytes: [u8; DOUBLE_BLOCK_LEN] = [0; DOUBLE_BLOCK_LEN];
+
+    bytes[..BLOCKHASHLEN].clone_from_slice(block_left);
+    bytes[BLOCKHASHLEN..].clone_from_slice(block_right);
+    sha256(&bytes)
+}
+
+/// Merkle root of `blocks`. `EMPTY_MERKLE_ROOT` when `blocks` is empty.
+pub(crate) fn merkle_root<T: Byteable>(blocks: &[T]) -> BlockHash {
+    let size = blocks.len();
+    match size {
+        0 => *EMPTY_MERKLE_ROOT.as_bytes(),
+        1 | 2 => {
+            let mut bytes : Vec<u8> = Vec::new();
+            if size == 1 {
+                bytes.append(&mut blocks[0].bytes());
+                bytes.append(&mut blocks[0].bytes());
+            } else {
+                bytes.append(&mut blocks[0].bytes());
+                bytes.append(&mut blocks[1].bytes());
+            }
+            sha256(&bytes)
+        },
+        _ => {
+            let (left, right) = blocks.split_at(size/2);
+            merkle_hash(&merkle_root(left), &merkle_root(right))
+        },
+    }
+}
+
+/// Merkle root of `blocks` computed with stack buffers.
+/// `offset` is the position of `blocks[0]` in the block data and `replace`
+/// optionally substitutes the item at an absolute position.
+pub(crate) fn fixed_merkle_root<T: FixedByteable>(blocks: &[T], offset: usize, replace: Option<(usize, &T)>) -> BlockHash {
+    let leaf = |index: usize| -> &T {
+        match replace {
+            Some((position, item)) if position == offset + index => item,
+            _ => &blocks[index],
+        }
+    };
+    let size = blocks.len();
+    let mut result: BlockHash = *EMPTY_MERKLE_ROOT.as_bytes();
+    match size {
+        0 => (),
+        1 | 2 => {
+            let second = if size == 1 { 0 } else { 1 };
+            let mut stack = [0u8; 2 * MAX_FIXED_LEN];
+            let mut heap : Vec<u8>;
+            let bytes : &mut [u8] = if T::LEN <= MAX_FIXED_LEN {
+                &mut stack[..2 * T::LEN]
+            } else {
+                heap = vec![0; 2 * T::LEN];
+                &mut heap
+            };
+            let (left, right) = bytes.split_at_mut(T::LEN);
+            leaf(0).write(left);
+            leaf(second).write(right);
+            result = sha256(bytes);
+        },
+        _ => {
+            let (left, right) = blocks.split_at(size/2);
+            result = merkle_hash(
+                &fixed_merkle_root(left, offset, replace),
+                &fixed_merkle_root(right, offset + size/2, replace));
+        },
+    }
+    result
+}
@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use super::*;
+use crate::blockencoding;
+
+/// Errors returned when receiving a block into an [`OrphanPool`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrphanError {
+    /// The block could not be decoded or is invalid.
+    Invalid(BlockError),
+    /// The block target is not the one of the chain parameters.
+    BadTarget,
+    /// The block is larger than `max_unsolicited_bytes` and was not requested.
+    Unrequested,
+    /// The peer already has `max_orphans_per_peer` blocks in the pool.
+    PeerFull,
+    /// The block is already in the pool.
+    Known,
+}
+
+impl fmt::Display for OrphanError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrphanError::Invalid(error) => write!(formatter, "invalid block: {}", error),
+            OrphanError::BadTarget => write!(formatter, "block target is not the target of the chain"),
+            OrphanError::Unrequested => write!(formatter, "large block was not requested"),
+            OrphanError::PeerFull => write!(formatter, "peer has too many orphan blocks"),
+            OrphanError::Known => write!(formatter, "block is already known"),
+        }
+    }
+}
+
+impl std::error::Error for OrphanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OrphanError::Invalid(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<BlockError> for OrphanError {
+    fn from(error: BlockError) -> OrphanError {
+        OrphanError::Invalid(error)
+    }
+}
+
+/// Blocks received from peers before their parent, waiting to be added to a
+/// [`Blockchain`].
+///
+/// # Description
+///
+/// The pool bounds what a peer can make a node store or compute by sending blocks:
+///
+/// * the header is decoded and its proof of work checked against the target of the
+///   [`ChainParams`] before the data is decoded, so an unmined block costs a hash;
+/// * each peer holds at most `max_orphans_per_peer` blocks, dropped with
+///   [`remove_peer`](OrphanPool::remove_peer) when the peer disconnects;
+/// * a block larger than `max_unsolicited_bytes` is only accepted after its header was
+///   announced and [`request`](OrphanPool::request)ed, so large bodies follow the
+///   announce then request flow.
+///
+/// Blocks are returned by [`take_children`](OrphanPool::take_children) once their
+/// parent is known.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { max_orphans_per_peer: 1, max_unsolicited_bytes: 200, ..ChainParams::default() };
+/// let genesis : OwnedBlock<String> = BlockchainBlock::genesis(&params, &[String::from("genesis")]).into_owned();
+/// let child = |parent: &BlockHeader, data: &[String]| -> OwnedBlock<String> {
+///     BlockchainBlock::builder().parent(parent).timestamp(parent.timestamp + 1).data(data).build().into_owned()
+/// };
+/// let small = child(genesis.header(), &[String::from("small")]);
+/// let large = child(genesis.header(), &vec![String::from("large"); 100]);
+///
+/// let mut pool : OrphanPool<String> = OrphanPool::new(params);
+/// assert_eq!(pool.receive(7, &small.to_bytes()), Ok(small.curr_hash()));
+/// assert_eq!(pool.receive(7, &small.to_bytes()), Err(OrphanError::Known));
+/// assert_eq!(pool.receive(8, &large.to_bytes()), Err(OrphanError::Unrequested));
+///
+/// pool.request(large.header()).unwrap();
+/// assert_eq!(pool.receive(7, &large.to_bytes()), Err(OrphanError::PeerFull));
+/// assert_eq!(pool.receive(8, &large.to_bytes()), Ok(large.curr_hash()));
+/// assert_eq!(pool.len(), 2);
+///
+/// let mut chain : Blockchain<String> = Blockchain::with_params(params);
+/// chain.push(genesis.seal().unwrap()).unwrap();
+/// for block in pool.take_children(&chain.tip().unwrap().curr_hash()) {
+///     chain.push(block).unwrap();
+/// }
+/// assert!(pool.is_empty());
+/// assert_eq!(chain.len(), 2);
+/// ```
+///
+/// A block whose hash does not meet its target is rejected before its data is decoded:
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let params = ChainParams { target: [0; BLOCKHASHLEN], ..ChainParams::default() };
+/// let block : OwnedBlock<i32> = BlockchainBlock::builder().target(params.target).data(&[1]).build().into_owned();
+/// let mut bytes = block.to_bytes();
+/// bytes.truncate(bytes.len() - 1);
+///
+/// let mut pool : OrphanPool<i32> = OrphanPool::new(params);
+/// assert_eq!(pool.receive(1, &bytes), Err(OrphanError::Invalid(BlockError::TargetNotMet)));
+/// assert_eq!(pool.request(block.header()), Err(OrphanError::Invalid(BlockError::TargetNotMet)));
+///
+/// let easy : OwnedBlock<i32> = BlockchainBlock::builder().data(&[1]).build().into_owned();
+/// assert_eq!(pool.receive(1, &easy.to_bytes()), Err(OrphanError::BadTarget));
+/// ```
+pub struct OrphanPool<T: Clone + 'static, H = ()> {
+    params: ChainParams,
+    /// hashes of the announced blocks requested from peers
+    requested: HashSet<Hash256>,
+    /// orphan blocks with the peer that sent them, keyed by block hash
+    orphans: HashMap<Hash256, (u64, SealedBlock<'static, T, H>)>,
+    /// number of orphan blocks of each peer
+    peers: HashMap<u64, usize>,
+}
+
+impl<T: Clone + 'static, H> OrphanPool<T, H> {
+    /// Constructs an empty `OrphanPool` for the chain described by `params`.
+    pub fn new(params: ChainParams) -> OrphanPool<T, H> {
+        OrphanPool { params, requested: HashSet::new(), orphans: HashMap::new(), peers: HashMap::new() }
+    }
+
+    /// Number of blocks in the pool.
+    pub fn len(&self) -> usize {
+        self.orphans.len()
+    }
+
+    /// Check the pool has no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.orphans.is_empty()
+    }
+
+    /// Check the block with hash `hash` is in the pool.
+    pub fn contains(&self, hash: &Hash256) -> bool {
+        self.orphans.contains_key(hash)
+    }
+
+    /// Stop waiting for the block with hash `hash`, requested with
+    /// [`request`](OrphanPool::request).
+    pub fn cancel(&mut self, hash: &Hash256) {
+        self.requested.remove(hash);
+    }
+
+    /// Remove the blocks following the block with hash `parent`, in no particular order.
+    pub fn take_children(&mut self, parent: &Hash256) -> Vec<SealedBlock<'static, T, H>> {
+        let children : Vec<Hash256> = self.orphans.iter()
+            .filter(|(_, (_, block))| block.prev_hash().as_ref() == Some(parent))
+            .map(|(hash, _)| *hash)
+            .collect();
+        children.iter().filter_map(|hash| self.remove(hash)).collect()
+    }
+
+    /// Remove the blocks sent by `peer`, returning how many were removed.
+    pub fn remove_peer(&mut self, peer: u64) -> usize {
+        let sent : Vec<Hash256> = self.orphans.iter()
+            .filter(|(_, (sender, _))| *sender == peer)
+            .map(|(hash, _)| *hash)
+            .collect();
+        sent.iter().filter_map(|hash| self.remove(hash)).count()
+    }
+
+    fn remove(&mut self, hash: &Hash256) -> Option<SealedBlock<'static, T, H>> {
+        let (peer, block) = self.orphans.remove(hash)?;
+        if let Some(count) = self.peers.get_mut(&peer) {
+            *count -= 1;
+            if *count == 0 { self.peers.remove(&peer); }
+        }
+        Some(block)
+    }
+
+    fn check_header(&self, header: &BlockHeader<H>) -> Result<(), OrphanError>
+    where
+        H: Byteable
+    {
+        if header.target != self.params.target { return Err(OrphanError::BadTarget); }
+        if !header.meets_target() { return Err(OrphanError::Invalid(BlockError::TargetNotMet)); }
+        Ok(())
+    }
+}
+
+impl<T, H> OrphanPool<T, H>
+where
+    T: FromBytes + Byteable + Clone + 'static,
+    H: FromBytes + Byteable
+{
+    /// Accept the block with header `header` whatever its size, once a peer announced it.
+    ///
+    /// Fails as [`receive`](OrphanPool::receive) does when the proof of work of the
+    /// header is not valid, so a peer cannot get unmined blocks requested.
+    pub fn request(&mut self, header: &BlockHeader<H>) -> Result<(), OrphanError> {
+        self.check_header(header)?;
+        self.requested.insert(header.hash());
+        Ok(())
+    }
+
+    /// Decode the block `bytes` sent by `peer` and keep it until its parent is known,
+    /// returning its hash.
+    ///
+    /// # Description
+    ///
+    /// Fails with `OrphanError::Invalid` when the block is larger than `max_block_bytes`,
+    /// cannot be decoded or is invalid, `OrphanError::BadTarget` when its target is not
+    /// the one of the chain parameters, `OrphanError::Known` when it is already in the
+    /// pool, `OrphanError::Unrequested` when it is larger than `max_unsolicited_bytes`
+    /// and was not requested, and `OrphanError::PeerFull` when `peer` already has
+    /// `max_orphans_per_peer` blocks in the pool. The data is only decoded once every
+    /// other check passed.
+    pub fn receive(&mut self, peer: u64, bytes: &[u8]) -> Result<Hash256, OrphanError> {
+        if bytes.len() > self.params.max_block_bytes { return Err(BlockError::TooLarge(bytes.len()).into()); }
+        let (header, _) = blockencoding::read_header::<H>(bytes)?;
+        self.check_header(&header)?;
+        let hash = header.hash();
+        if self.orphans.contains_key(&hash) { return Err(OrphanError::Known); }
+        if bytes.len() > self.params.max_unsolicited_bytes && !self.requested.contains(&hash) {
+            return Err(OrphanError::Unrequested);
+        }
+        if self.peers.get(&peer).copied().unwrap_or(0) >= self.params.max_orphans_per_peer {
+            return Err(OrphanError::PeerFull);
+        }
+
+        let block = BlockchainBlock::from_bytes_limited(bytes, self.params.max_block_bytes)?.seal()?;
+        self.requested.remove(&hash);
+        *self.peers.entry(peer).or_insert(0) += 1;
+        self.orphans.insert(hash, (peer, block));
+        Ok(hash)
+    }
+}
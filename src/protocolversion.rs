@@ -0,0 +1,108 @@
+use std::convert::TryFrom;
+use std::fmt;
+use super::*;
+
+/// Versions of the protocol known to this crate, as stamped in block headers.
+///
+/// A variant is added each time the header layout or the hashing rules change, so
+/// nodes can keep validating blocks of the versions they still accept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProtocolVersion {
+    /// First version of the protocol, hashing the header together with the data.
+    V1 = 1,
+    /// Blocks split into a header and a body, the hash covering the header only and
+    /// the data being committed to by the merkle root.
+    V2 = 2,
+    /// Headers carrying the height, the difficulty target and the chain identifier.
+    V3 = 3,
+}
+
+impl ProtocolVersion {
+    /// Version used by default to create blocks, `VERSION`.
+    pub const CURRENT: ProtocolVersion = ProtocolVersion::V3;
+
+    /// Version number as stamped in headers.
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for ProtocolVersion {
+    type Error = BlockError;
+
+    /// Fails with `BlockError::BadVersion` for unknown version numbers.
+    fn try_from(version: u8) -> Result<ProtocolVersion, BlockError> {
+        match version {
+            1 => Ok(ProtocolVersion::V1),
+            2 => Ok(ProtocolVersion::V2),
+            3 => Ok(ProtocolVersion::V3),
+            _ => Err(BlockError::BadVersion(version)),
+        }
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.as_u8())
+    }
+}
+
+/// Range of protocol versions a node accepts, inclusive.
+///
+/// During an upgrade nodes accept both the old and the new version, then raise
+/// `min` once every producer has switched.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// assert_eq!(ProtocolVersion::CURRENT.as_u8(), VERSION);
+/// let policy = VersionPolicy::default();
+/// assert!(policy.accepts(VERSION));
+/// assert!(!policy.accepts(0));
+/// assert!(!policy.accepts(ProtocolVersion::V2.as_u8()));
+/// assert!(VersionPolicy::new(ProtocolVersion::V2, ProtocolVersion::V3).accepts(2));
+///
+/// let block : BlockchainBlock<i32> = BlockchainBlock::builder().protocol_version(ProtocolVersion::V3).push(5).build();
+/// assert_eq!(block.validate_version(&policy), Ok(()));
+/// let unknown : BlockchainBlock<i32> = BlockchainBlock::builder().version(9).push(5).build();
+/// assert_eq!(unknown.validate_version(&policy), Err(BlockError::BadVersion(9)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionPolicy {
+    /// oldest accepted version
+    pub min: ProtocolVersion,
+    /// newest accepted version
+    pub max: ProtocolVersion,
+}
+
+impl VersionPolicy {
+    /// Constructs a new `VersionPolicy` accepting the versions from `min` to `max`.
+    pub fn new(min: ProtocolVersion, max: ProtocolVersion) -> VersionPolicy {
+        VersionPolicy { min, max }
+    }
+
+    /// Check the header version number `version` is accepted.
+    pub fn accepts(&self, version: u8) -> bool {
+        self.validate(version).is_ok()
+    }
+
+    /// Check the header version number `version` is accepted.
+    /// Fails with `BlockError::BadVersion` otherwise.
+    pub fn validate(&self, version: u8) -> Result<ProtocolVersion, BlockError> {
+        let protocol_version = ProtocolVersion::try_from(version)?;
+        if protocol_version < self.min || protocol_version > self.max {
+            return Err(BlockError::BadVersion(version));
+        }
+        Ok(protocol_version)
+    }
+}
+
+/// Accepts the current version only.
+impl Default for VersionPolicy {
+    fn default() -> VersionPolicy {
+        VersionPolicy::new(ProtocolVersion::CURRENT, ProtocolVersion::CURRENT)
+    }
+}
@@ -0,0 +1,130 @@
+use std::ops::Range;
+use super::*;
+
+/// Default number of items returned by a [`Query`].
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Block data items a [`Query`] can filter.
+pub trait Queryable {
+    /// Kind of payload of the item, chosen by the application.
+    fn kind(&self) -> u32;
+    /// Identifier of the submitter of the item.
+    fn submitter(&self) -> u64;
+}
+
+/// Item of a block found by a [`Query`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueryMatch<'c, T> {
+    /// height of the block holding the item
+    pub height: u64,
+    /// position of the item in the block data
+    pub position: usize,
+    /// the item
+    pub item: &'c T,
+}
+
+/// Filter over the items of the blocks of a [`Blockchain`].
+///
+/// # Description
+///
+/// [`Blockchain::query`] visits the blocks of the height range in increasing height
+/// order, reaching the first one directly, and stops once `limit` items matched: a
+/// query costs at most the number of items of the blocks of its range, whatever the
+/// length of the chain. By default the range is the whole chain and the limit is
+/// [`DEFAULT_QUERY_LIMIT`].
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// #[derive(Clone)]
+/// struct Reading { sensor: u64, kind: u32 }
+///
+/// impl Byteable for Reading {
+///     fn bytes(&self) -> Vec<u8> { [&self.sensor.to_le_bytes()[..], &self.kind.to_le_bytes()[..]].concat() }
+/// }
+/// impl Queryable for Reading {
+///     fn kind(&self) -> u32 { self.kind }
+///     fn submitter(&self) -> u64 { self.sensor }
+/// }
+///
+/// let mut chain : Blockchain<Reading> = Blockchain::new();
+/// for height in 0..5 {
+///     let data : Vec<Reading> = (0..4).map(|sensor| Reading { sensor, kind: (height % 2) as u32 }).collect();
+///     let block : OwnedBlock<Reading> = match chain.tip() {
+///         None => BlockchainBlock::builder().data(&data).build().into_owned(),
+///         Some(tip) => BlockchainBlock::builder().parent(tip.header()).timestamp(height).data(&data).build().into_owned(),
+///     };
+///     chain.push(block.seal().unwrap()).unwrap();
+/// }
+///
+/// let query = Query::new().height_range(1..5).payload_kind(1).submitter(2);
+/// let heights : Vec<u64> = chain.query(&query).iter().map(|found| found.height).collect();
+/// assert_eq!(heights, [1, 3]);
+/// assert_eq!(chain.query(&query.limit(1)).len(), 1);
+/// assert_eq!(chain.query(&Query::new().payload_kind(0)).len(), 3 * 4);
+/// assert!(chain.query(&Query::new().height_range(5..9)).is_empty());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Query {
+    heights: Range<u64>,
+    kind: Option<u32>,
+    submitter: Option<u64>,
+    limit: usize,
+}
+
+impl Query {
+    /// Constructs a `Query` matching every item of the chain, up to [`DEFAULT_QUERY_LIMIT`].
+    pub fn new() -> Query {
+        Query { heights: 0..u64::MAX, kind: None, submitter: None, limit: DEFAULT_QUERY_LIMIT }
+    }
+
+    /// Only match the items of the blocks with a height in `heights`.
+    pub fn height_range(self, heights: Range<u64>) -> Query {
+        Query { heights, ..self }
+    }
+
+    /// Only match the items of kind `kind`.
+    pub fn payload_kind(self, kind: u32) -> Query {
+        Query { kind: Some(kind), ..self }
+    }
+
+    /// Only match the items submitted by `submitter`.
+    pub fn submitter(self, submitter: u64) -> Query {
+        Query { submitter: Some(submitter), ..self }
+    }
+
+    /// Return at most `limit` items.
+    pub fn limit(self, limit: usize) -> Query {
+        Query { limit, ..self }
+    }
+
+    fn matches<T: Queryable>(&self, item: &T) -> bool {
+        self.kind.is_none_or(|kind| item.kind() == kind)
+            && self.submitter.is_none_or(|submitter| item.submitter() == submitter)
+    }
+}
+
+impl Default for Query {
+    fn default() -> Query {
+        Query::new()
+    }
+}
+
+impl<T: Queryable + Clone + 'static, H> Blockchain<T, H> {
+    /// Run `query` over the blocks of the chain, returning the matching items in chain
+    /// order.
+    pub fn query(&self, query: &Query) -> Vec<QueryMatch<'_, T>> {
+        let first = self.genesis().map_or(0, |genesis| genesis.height()).max(query.heights.start);
+        (first..query.heights.end)
+            .map_while(|height| self.at_height(height))
+            .flat_map(|block| block.data().iter().enumerate().map(move |(position, item)| {
+                QueryMatch { height: block.height(), position, item }
+            }))
+            .filter(|found| query.matches(found.item))
+            .take(query.limit)
+            .collect()
+    }
+}
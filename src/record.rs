@@ -0,0 +1,175 @@
+use super::*;
+
+/// Domain separation prefix of the field leaves.
+const LEAF_PREFIX: u8 = 0x00;
+/// Domain separation prefix of the inner nodes.
+const NODE_PREFIX: u8 = 0x01;
+
+fn field_salt(secret: &Salt, index: usize) -> Salt {
+    let mut bytes : Vec<u8> = Vec::with_capacity(SALTLEN + 8);
+    bytes.extend_from_slice(secret);
+    bytes.extend_from_slice(&(index as u64).to_le_bytes());
+    sha256(&bytes)
+}
+
+fn field_leaf(salt: &Salt, name: &str, value: &[u8]) -> BlockHash {
+    let mut bytes : Vec<u8> = Vec::with_capacity(1 + SALTLEN + 16 + name.len() + value.len());
+    bytes.push(LEAF_PREFIX);
+    bytes.extend_from_slice(salt);
+    bytes.extend_from_slice(&(name.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(value);
+    sha256(&bytes)
+}
+
+fn node_hash(left: &BlockHash, right: &BlockHash) -> BlockHash {
+    let mut bytes = [0u8; 1 + 2 * BLOCKHASHLEN];
+    bytes[0] = NODE_PREFIX;
+    bytes[1..=BLOCKHASHLEN].copy_from_slice(left);
+    bytes[1 + BLOCKHASHLEN..].copy_from_slice(right);
+    sha256(&bytes)
+}
+
+/// Hash one level of the tree. An odd node at the end is carried up unchanged.
+fn next_level(level: &[BlockHash]) -> Vec<BlockHash> {
+    level.chunks(2).map(|pair| match pair {
+        [left, right] => node_hash(left, right),
+        [single] => *single,
+        _ => unreachable!(),
+    }).collect()
+}
+
+/// Commitment to a structured record, stored as an item of a block.
+///
+/// Each field is a salted leaf of a small merkle tree, so a single field can
+/// later be disclosed with a [`FieldProof`] without revealing the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RecordCommitment {
+    /// root of the merkle tree of the fields
+    pub root: BlockHash,
+    /// number of fields in the record
+    pub field_count: u64,
+}
+
+impl Byteable for RecordCommitment {
+    fn bytes(&self) -> Vec<u8> {
+        let mut data = self.root.to_vec();
+        data.extend_from_slice(&self.field_count.to_le_bytes());
+        data
+    }
+}
+
+/// Structured record kept by its holder: named fields and the secret salting them.
+#[derive(Clone, Debug)]
+pub struct Record {
+    fields: Vec<(String, Vec<u8>)>,
+    secret: Salt,
+}
+
+impl Record {
+    /// Constructs a new `Record`.
+    ///
+    /// # Description
+    ///
+    /// * `fields` - Field names and values, in a fixed order.
+    /// * `secret` - Random secret every field salt is derived from. It must
+    ///   not be disclosed, otherwise low-entropy fields can be guessed.
+    pub fn new<V: Byteable>(fields: &[(String, V)], secret: Salt) -> Record {
+        Record {
+            fields: fields.iter().map(|(name, value)| (name.clone(), value.bytes())).collect(),
+            secret,
+        }
+    }
+
+    fn leaves(&self) -> Vec<BlockHash> {
+        self.fields.iter().enumerate()
+            .map(|(index, (name, value))| field_leaf(&field_salt(&self.secret, index), name, value))
+            .collect()
+    }
+
+    /// Calculate the commitment to be stored in a block.
+    pub fn commitment(&self) -> RecordCommitment {
+        let mut level = self.leaves();
+        if level.is_empty() { level.push(sha256(&[LEAF_PREFIX])); }
+        while level.len() > 1 { level = next_level(&level); }
+        RecordCommitment { root: level[0], field_count: self.fields.len() as u64 }
+    }
+
+    /// Build the disclosure proof of the field at `index`.
+    /// Returns `None` when `index` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let diploma = Record::new(&[
+    ///     (String::from("name"), String::from("Ada")),
+    ///     (String::from("degree"), String::from("Mathematics")),
+    ///     (String::from("grade"), String::from("A")),
+    /// ], [7; SALTLEN]);
+    /// let commitment = diploma.commitment();
+    ///
+    /// let proof = diploma.prove(2).unwrap();
+    /// assert_eq!(proof.name, "grade");
+    /// assert_eq!(proof.value, b"A".to_vec());
+    /// assert!(proof.verify(&commitment));
+    /// ```
+    pub fn prove(&self, index: usize) -> Option<FieldProof> {
+        let (name, value) = self.fields.get(index)?;
+        let mut path : Vec<BlockHash> = Vec::new();
+        let mut level = self.leaves();
+        let mut position = index;
+        while level.len() > 1 {
+            let sibling = position ^ 1;
+            if sibling < level.len() { path.push(level[sibling]); }
+            level = next_level(&level);
+            position /= 2;
+        }
+        Some(FieldProof {
+            index: index as u64,
+            name: name.clone(),
+            value: value.clone(),
+            salt: field_salt(&self.secret, index),
+            path,
+        })
+    }
+}
+
+/// Proof that one field with a given value is part of a [`RecordCommitment`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldProof {
+    /// position of the field in the record
+    pub index: u64,
+    /// name of the disclosed field
+    pub name: String,
+    /// bytes of the disclosed value
+    pub value: Vec<u8>,
+    /// salt of the disclosed field
+    pub salt: Salt,
+    /// sibling hashes from the leaf up to the root
+    pub path: Vec<BlockHash>,
+}
+
+impl FieldProof {
+    /// Check the disclosed field against a commitment.
+    pub fn verify(&self, commitment: &RecordCommitment) -> bool {
+        if self.index >= commitment.field_count { return false; }
+        let mut hash = field_leaf(&self.salt, &self.name, &self.value);
+        let mut path = self.path.iter();
+        let mut position = self.index;
+        let mut width = commitment.field_count;
+        while width > 1 {
+            let sibling = position ^ 1;
+            if sibling < width {
+                let other = match path.next() { Some(other) => other, None => return false };
+                hash = if position & 1 == 0 { node_hash(&hash, other) } else { node_hash(other, &hash) };
+            }
+            position /= 2;
+            width = width.div_ceil(2);
+        }
+        path.next().is_none() && hash == commitment.root
+    }
+}
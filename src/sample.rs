@@ -0,0 +1,124 @@
+//! Reproducible random samples of a chain, for spot audits.
+//!
+//! The heights are picked by hashing a seed chosen by the auditor: the `i`-th pick uses
+//! the first 8 bytes of `SHA256(seed || i)`, `i` as an 8-byte little endian integer,
+//! read as a little endian integer, to draw without replacement among the heights not
+//! picked yet. The same seed always gives the same heights, so anyone can check a
+//! sample was not chosen by the party serving it.
+
+use std::collections::HashMap;
+use super::*;
+use crate::epoch;
+use crate::inclusion;
+
+/// Block sampled from a chain, with the proof it is one of the summarized blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sample<T: Clone + 'static, H = ()> {
+    /// the sampled block, header and data
+    pub block: OwnedBlock<T, H>,
+    /// proof the hash of the block is in `Audit::summary`
+    pub proof: InclusionProof,
+}
+
+/// Random sample of the blocks of a chain, see [`Blockchain::sample_blocks`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Audit<T: Clone + 'static, H = ()> {
+    /// summary of every block of the chain
+    pub summary: EpochSummary,
+    /// the sampled blocks, by increasing height
+    pub samples: Vec<Sample<T, H>>,
+}
+
+impl<T, H> Audit<T, H>
+where
+    T: Byteable + Clone + 'static,
+    H: Byteable
+{
+    /// Check the audit holds the `k` blocks picked by `seed`, each valid and proven to
+    /// be part of the summary.
+    ///
+    /// The summary itself is not checked: compare its root with one obtained from a
+    /// trusted source, such as a summary published by the chain operator.
+    pub fn verify(&self, seed: &[u8], k: usize) -> bool {
+        let heights = sample_heights(seed, self.summary.first_height, self.summary.len, k);
+        heights.len() == self.samples.len()
+            && heights.iter().zip(&self.samples).all(|(height, sample)| {
+                sample.block.height() == *height
+                    && sample.block.verify().is_ok()
+                    && self.summary.check_block(&sample.block, &sample.proof)
+            })
+    }
+}
+
+impl<T, H> Blockchain<T, H>
+where
+    T: Byteable + Clone + 'static,
+    H: Byteable + Clone
+{
+    /// Pick `k` blocks of the chain from `seed`, or every block when the chain has no
+    /// more than `k` blocks. Returns `None` when the chain is empty.
+    ///
+    /// # Description
+    ///
+    /// The blocks come with a summary of the whole chain and their proof of inclusion in
+    /// it, built hashing each node of the merkle tree once, so the work grows with the
+    /// length of the chain and not with `k` times that length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate blockchainblock;
+    /// use crate::blockchainblock::*;
+    ///
+    /// let params = ChainParams { genesis_timestamp: 1524885322, ..ChainParams::default() };
+    /// let mut chain : Blockchain<BlockHash> = Blockchain::with_params(params);
+    /// for block in fixtures::chain(&params, 7, 20) {
+    ///     chain.push(block.seal().unwrap()).unwrap();
+    /// }
+    ///
+    /// let audit = chain.sample_blocks(b"audit 2026", 5).unwrap();
+    /// assert_eq!(audit.samples.len(), 5);
+    /// assert!(audit.verify(b"audit 2026", 5));
+    /// assert!(!audit.verify(b"audit 2027", 5));
+    /// assert_eq!(chain.sample_blocks(b"audit 2026", 5), Some(audit.clone()));
+    /// assert!(audit.summary.verify(&chain));
+    ///
+    /// let heights : Vec<u64> = chain.sample_blocks(b"all", 50).unwrap().samples.iter().map(|sample| sample.block.height()).collect();
+    /// assert_eq!(heights, (0..20).collect::<Vec<u64>>());
+    /// ```
+    pub fn sample_blocks(&self, seed: &[u8], k: usize) -> Option<Audit<T, H>> {
+        let first_height = self.genesis()?.height();
+        let len = self.len() as u64;
+        let summary = EpochSummary::of(self, first_height, len)?;
+        let blocks = epoch::blocks(self, first_height, len)?;
+        let hashes : Vec<Hash256> = blocks.iter().map(|block| block.curr_hash()).collect();
+        let heights = sample_heights(seed, first_height, len, k);
+        let positions : Vec<usize> = heights.iter().map(|height| (height - first_height) as usize).collect();
+        let proofs = inclusion::prove_positions(&hashes, &positions)?;
+        let samples = positions.iter().zip(proofs).map(|(position, proof)| {
+            Sample { block: OwnedBlock::from(blocks[*position].block()), proof }
+        }).collect();
+        Some(Audit { summary, samples })
+    }
+}
+
+/// Heights of `k` of the `len` blocks from `first_height` picked by `seed`, increasing.
+fn sample_heights(seed: &[u8], first_height: u64, len: u64, k: usize) -> Vec<u64> {
+    let k = (k as u64).min(len);
+    // partial Fisher-Yates shuffle of the offsets, only storing the moved ones
+    let mut moved : HashMap<u64, u64> = HashMap::new();
+    let mut heights : Vec<u64> = Vec::with_capacity(k as usize);
+    for pick in 0..k {
+        let mut input = seed.to_vec();
+        input.extend_from_slice(&pick.to_le_bytes());
+        let mut random = [0; 8];
+        random.copy_from_slice(&sha256(&input)[..8]);
+        let other = pick + u64::from_le_bytes(random) % (len - pick);
+        let picked = moved.get(&other).copied().unwrap_or(other);
+        let current = moved.get(&pick).copied().unwrap_or(pick);
+        moved.insert(other, current);
+        heights.push(first_height + picked);
+    }
+    heights.sort_unstable();
+    heights
+}
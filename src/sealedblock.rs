@@ -0,0 +1,119 @@
+use std::fmt;
+use std::ops::Deref;
+use super::*;
+
+/// A block that passed [`verify`](BlockchainBlock::verify) and can no longer be modified.
+///
+/// # Description
+///
+/// The stored hash matches the header and meets its difficulty target, the merkle root
+/// matches the data and the version is the current protocol `VERSION`: a sealed block
+/// was mined. A sealed block is obtained from
+/// [`seal`](BlockchainBlock::seal) or [`try_new`](BlockchainBlock::try_new) and gives
+/// read-only access to the block it wraps, so these invariants hold for its whole life.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 2] = [5, 6];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// let tampered : BlockchainBlock<i32> = BlockchainBlock::from_parts(*block.header(), BlockBody::new(&data[..1]));
+/// assert_eq!(tampered.seal(), Err(BlockError::MerkleMismatch));
+///
+/// let sealed : SealedBlock<i32> = block.clone().seal().unwrap();
+/// assert_eq!(sealed.curr_hash(), block.curr_hash());
+/// assert_eq!(sealed.data(), &data);
+///
+/// let mut block = sealed.into_inner();
+/// block.set_nonce(4);
+/// assert!(block.seal().is_ok());
+/// ```
+pub struct SealedBlock<'a, T: Clone, H = ()> {
+    block: BlockchainBlock<'a, T, H>,
+}
+
+impl<'a, T, H> BlockchainBlock<'a, T, H>
+where
+    T: Byteable + Clone,
+    H: Byteable
+{
+    /// Verify the block as [`verify`](BlockchainBlock::verify) does and seal it.
+    pub fn seal(self) -> Result<SealedBlock<'a, T, H>, BlockError> {
+        self.verify()?;
+        Ok(SealedBlock { block: self })
+    }
+}
+
+impl<'a, T: Clone, H> SealedBlock<'a, T, H> {
+    /// Wrap a block known to be valid, such as one just built from its data.
+    pub(crate) fn new_unchecked(block: BlockchainBlock<'a, T, H>) -> SealedBlock<'a, T, H> {
+        SealedBlock { block }
+    }
+
+    /// Return the sealed block.
+    pub fn block(&self) -> &BlockchainBlock<'a, T, H> {
+        &self.block
+    }
+
+    /// Unseal the block, to modify it.
+    pub fn into_inner(self) -> BlockchainBlock<'a, T, H> {
+        self.block
+    }
+
+    /// Convert the sealed block into one owning its data, see
+    /// [`into_owned`](BlockchainBlock::into_owned).
+    pub fn into_owned(self) -> SealedBlock<'static, T, H>
+    where
+        T: Byteable + 'static,
+        H: Byteable
+    {
+        SealedBlock { block: self.block.into_owned() }
+    }
+}
+
+impl<'a, T: Clone, H> Deref for SealedBlock<'a, T, H> {
+    type Target = BlockchainBlock<'a, T, H>;
+
+    fn deref(&self) -> &BlockchainBlock<'a, T, H> {
+        &self.block
+    }
+}
+
+impl<'a, T: Clone, H: Clone> Clone for SealedBlock<'a, T, H> {
+    fn clone(&self) -> SealedBlock<'a, T, H> {
+        SealedBlock { block: self.block.clone() }
+    }
+}
+
+impl<'a, 'b, T: Clone, H> PartialEq<SealedBlock<'b, T, H>> for SealedBlock<'a, T, H> {
+    fn eq(&self, other: &SealedBlock<'b, T, H>) -> bool {
+        self.block == other.block
+    }
+}
+
+impl<'a, T: Clone, H> Eq for SealedBlock<'a, T, H> {}
+
+impl<'a, T: Clone, H> std::hash::Hash for SealedBlock<'a, T, H> {
+    fn hash<S: std::hash::Hasher>(&self, state: &mut S) {
+        self.block.hash(state);
+    }
+}
+
+impl<'a, T, H> fmt::Debug for SealedBlock<'a, T, H>
+where
+    BlockchainBlock<'a, T, H>: fmt::Debug,
+    T: Clone
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("SealedBlock").field(&self.block).finish()
+    }
+}
+
+impl<'a, T: Clone, H> fmt::Display for SealedBlock<'a, T, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.block, formatter)
+    }
+}
@@ -0,0 +1,139 @@
+//! Append-only topics on a chain.
+//!
+//! A chain of [`TopicMessage`] items is a log of named streams: a [`Topic`] publishes
+//! messages under its name and subscribers read them back in chain order, each with the
+//! proof that the block holding it committed to it, so a message log served by a
+//! third party can be checked against the block headers alone.
+//!
+//! A message is encoded as the length of the topic name as a LEB128 varint, the name
+//! in UTF-8 and the payload.
+
+use super::*;
+use crate::varint;
+
+/// Message of a [`Topic`], stored as an item of a block.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TopicMessage {
+    /// name of the topic
+    pub topic: String,
+    /// content of the message
+    pub payload: Vec<u8>,
+}
+
+impl Byteable for TopicMessage {
+    fn bytes(&self) -> Vec<u8> {
+        let mut out : Vec<u8> = Vec::new();
+        varint::write_u64(&mut out, self.topic.len() as u64);
+        out.extend_from_slice(self.topic.as_bytes());
+        out.extend_from_slice(&self.payload);
+        out
+    }
+}
+
+impl FromBytes for TopicMessage {
+    fn from_bytes(bytes: &[u8]) -> Option<TopicMessage> {
+        let mut pos = 0;
+        let len = varint::read_u64(bytes, &mut pos)?;
+        if len > (bytes.len() - pos) as u64 { return None; }
+        let end = pos + len as usize;
+        let topic = String::from_utf8(bytes[pos..end].to_vec()).ok()?;
+        Some(TopicMessage { topic, payload: bytes[end..].to_vec() })
+    }
+}
+
+/// Message of a [`Topic`] read from a chain, with the proof of its inclusion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    /// height of the block holding the message
+    pub height: u64,
+    /// hash of the block holding the message
+    pub block: Hash256,
+    /// content of the message
+    pub payload: Vec<u8>,
+    /// proof the block data holds the message
+    pub proof: InclusionProof,
+}
+
+/// Named stream of messages stored on a chain.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let alerts = Topic::new("alerts");
+/// let metrics = Topic::new("metrics");
+/// let mut chain : Blockchain<TopicMessage> = Blockchain::new();
+/// let genesis : OwnedBlock<TopicMessage> = BlockchainBlock::builder()
+///     .push(alerts.publish(b"started"))
+///     .push(metrics.publish(b"cpu 3"))
+///     .build()
+///     .into_owned();
+/// chain.push(genesis.seal().unwrap()).unwrap();
+/// let tip = *chain.tip().unwrap().header();
+/// let block : OwnedBlock<TopicMessage> = BlockchainBlock::builder()
+///     .parent(&tip)
+///     .timestamp(tip.timestamp + 1)
+///     .push(metrics.publish(b"cpu 5"))
+///     .push(alerts.publish(b"disk full"))
+///     .build()
+///     .into_owned();
+/// chain.push(block.seal().unwrap()).unwrap();
+///
+/// let deliveries : Vec<Delivery> = alerts.subscribe(&chain, 0).collect();
+/// let payloads : Vec<&[u8]> = deliveries.iter().map(|delivery| &delivery.payload[..]).collect();
+/// assert_eq!(payloads, [&b"started"[..], &b"disk full"[..]]);
+/// assert_eq!(alerts.subscribe(&chain, 1).count(), 1);
+///
+/// let header = chain.at_height(1).unwrap().header();
+/// assert!(alerts.verify(&deliveries[1], header));
+/// assert!(!metrics.verify(&deliveries[1], header));
+/// assert!(!alerts.verify(&deliveries[0], header));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Topic {
+    name: String,
+}
+
+impl Topic {
+    /// Constructs the `Topic` named `name`.
+    pub fn new(name: &str) -> Topic {
+        Topic { name: name.to_string() }
+    }
+
+    /// Name of the topic.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Build the message of the topic holding `payload`, to be pushed in a block.
+    pub fn publish(&self, payload: &[u8]) -> TopicMessage {
+        TopicMessage { topic: self.name.clone(), payload: payload.to_vec() }
+    }
+
+    /// Iterate over the messages of the topic in the blocks of `chain` from height
+    /// `from_height` to the tip, in block order then in data order.
+    pub fn subscribe<'c, H>(&'c self, chain: &'c Blockchain<TopicMessage, H>, from_height: u64) -> impl Iterator<Item = Delivery> + 'c {
+        let first = chain.genesis().map_or(0, |genesis| genesis.height()).max(from_height);
+        (first..).map_while(move |height| chain.at_height(height))
+            .flat_map(move |block| {
+                block.data().iter().enumerate()
+                    .filter(|(_, message)| message.topic == self.name)
+                    .filter_map(|(position, message)| Some(Delivery {
+                        height: block.height(),
+                        block: block.curr_hash(),
+                        payload: message.payload.clone(),
+                        proof: block.inclusion_proof(position)?,
+                    }))
+                    .collect::<Vec<Delivery>>()
+            })
+    }
+
+    /// Check `delivery` is a message of the topic held by the block with header `header`.
+    pub fn verify<H: Byteable>(&self, delivery: &Delivery, header: &BlockHeader<H>) -> bool {
+        header.height == delivery.height
+            && header.hash() == delivery.block
+            && delivery.proof.verify(&self.publish(&delivery.payload), &header.merkle_root)
+    }
+}
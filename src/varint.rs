@@ -0,0 +1,50 @@
+//! LEB128 variable length integers used by the compact wire formats.
+
+/// Append `value` to `out` as an unsigned LEB128 varint.
+pub(crate) fn write_u64(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Number of bytes `write_u64` appends for `value`.
+pub(crate) fn len_u64(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// Read an unsigned LEB128 varint from `bytes` starting at `*pos`.
+/// Returns `None` when the input ends early, the value overflows 64 bits or
+/// the encoding is not the shortest one, so every value has a single encoding.
+pub(crate) fn read_u64(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        if shift == 63 && byte > 1 { return None; }
+        result |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 0 { return None; }
+            return Some(result);
+        }
+        shift += 7;
+        if shift > 63 { return None; }
+    }
+}
+
+/// Map a signed delta onto an unsigned value so small magnitudes stay short.
+pub(crate) fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Inverse of [`zigzag`].
+pub(crate) fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
@@ -0,0 +1,167 @@
+//! Pure verification functions, free of any storage or network dependency.
+//!
+//! Every check only needs the data it is given, so a verifier can be built from
+//! these functions alone.
+
+use std::fmt;
+use super::*;
+
+/// Errors returned when verifying a single header or a foreign proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The hash calculated from the header is not the expected one.
+    HashMismatch,
+    /// The hash of the header exceeds its difficulty target.
+    TargetNotMet,
+    /// The header stream could not be decoded.
+    BadHeaders(HeaderSyncError),
+    /// The first header is not a trusted checkpoint.
+    UntrustedCheckpoint,
+    /// A header does not follow the one before it.
+    Unlinked,
+    /// A header belongs to another chain, holding its chain identifier.
+    ChainMismatch(u32),
+    /// A header target is not the one of the chain parameters.
+    BadTarget,
+    /// The item is not at the proven position of the block.
+    NotIncluded,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::HashMismatch => write!(formatter, "header hash does not match the expected one"),
+            VerifyError::TargetNotMet => write!(formatter, "header hash exceeds the difficulty target"),
+            VerifyError::BadHeaders(error) => write!(formatter, "invalid header stream: {}", error),
+            VerifyError::UntrustedCheckpoint => write!(formatter, "first header is not a trusted checkpoint"),
+            VerifyError::Unlinked => write!(formatter, "header does not follow the previous one"),
+            VerifyError::ChainMismatch(chain_id) => write!(formatter, "header belongs to chain {}", chain_id),
+            VerifyError::BadTarget => write!(formatter, "header target is not the target of the chain"),
+            VerifyError::NotIncluded => write!(formatter, "item is not included in the block"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Check `header` hashes to `hash` and meets its difficulty target.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 1] = [5];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// assert_eq!(verify::header(block.header(), &block.curr_hash()), Ok(()));
+/// assert_eq!(verify::header(block.header(), &Hash256::ZERO), Err(verify::VerifyError::HashMismatch));
+///
+/// let hard : BlockchainBlock<i32> = BlockchainBlock::builder().target([0; BLOCKHASHLEN]).data(&data).build();
+/// assert_eq!(verify::header(hard.header(), &hard.curr_hash()), Err(verify::VerifyError::TargetNotMet));
+/// ```
+pub fn header<H: Byteable>(header: &BlockHeader<H>, hash: &Hash256) -> Result<(), VerifyError> {
+    let calculated = header.hash();
+    if calculated != *hash { return Err(VerifyError::HashMismatch); }
+    if calculated.as_bytes() > &header.target { return Err(VerifyError::TargetNotMet); }
+    Ok(())
+}
+
+/// Check every header in `headers` directly follows the one before it.
+/// An empty or single-header list is a valid chain.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 1] = [5];
+/// let first : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// let second : BlockchainBlock<i32> = BlockchainBlock::builder().parent(first.header()).timestamp(5).data(&data).build();
+/// assert!(verify::header_chain(&[*first.header(), *second.header()]));
+/// assert!(!verify::header_chain(&[*second.header(), *first.header()]));
+/// ```
+pub fn header_chain<H: Byteable>(headers: &[BlockHeader<H>]) -> bool {
+    headers.windows(2).all(|pair| pair[1].follows(&pair[0]))
+}
+
+/// Check `body` is the one committed to by `header`.
+pub fn body<T: Byteable + Clone, H>(header: &BlockHeader<H>, body: &BlockBody<T>) -> bool {
+    body.matches(header)
+}
+
+/// Check a revealed value was committed to in `commit_block`.
+pub fn reveal<T: Byteable>(reveal: &Reveal<T>, commit_block: &BlockchainBlock<Commitment>) -> bool {
+    reveal.verify(commit_block)
+}
+
+/// Check a disclosed field belongs to the record committed to by `commitment`.
+pub fn field_proof(proof: &FieldProof, commitment: &RecordCommitment) -> bool {
+    proof.verify(commitment)
+}
+
+/// Check a delay proof of at most `max_ticks` ticks by recomputing the sequence from `seed`.
+pub fn delay_proof(proof: &DelayProof, seed: &BlockHash, max_ticks: u64) -> bool {
+    proof.verify(seed, max_ticks)
+}
+
+/// Check `item` is part of the data committed to by `header`, see [`InclusionProof`].
+pub fn inclusion_proof<T: Byteable, H>(proof: &InclusionProof, item: &T, header: &BlockHeader<H>) -> bool {
+    proof.verify(item, &header.merkle_root)
+}
+
+/// Check an inclusion proof produced by another chain, trusting only checkpoints of
+/// that chain.
+///
+/// # Description
+///
+/// * `params` - Parameters of the foreign chain, giving its identifier and target.
+/// * `checkpoints` - Hashes of trusted headers of the foreign chain.
+/// * `header_bytes` - Header stream, see [`encode_headers`], from a checkpoint to the
+///   header of the block holding `item`.
+/// * `proof` - Inclusion proof of `item` in the last block of the stream.
+///
+/// Fails with `BadHeaders` when the stream can not be decoded, `UntrustedCheckpoint`
+/// when it does not start with a checkpoint, `Unlinked` when a header does not follow
+/// the one before it, `ChainMismatch` or `BadTarget` when a header is not of the chain
+/// of `params`, `TargetNotMet` when a header after the checkpoint was not mined and
+/// `NotIncluded` when the proof does not hold for the last header.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let foreign = ChainParams { genesis_timestamp: 1524885322, chain_id: 9, ..ChainParams::default() };
+/// let chain = fixtures::chain(&foreign, 7, 4);
+/// let checkpoints = [chain[1].curr_hash()];
+/// let headers : Vec<BlockHeader> = chain[1..].iter().map(|block| *block.header()).collect();
+/// let stream = encode_headers(&headers);
+/// let item = chain[3].data()[0];
+/// let proof = chain[3].inclusion_proof(0).unwrap();
+/// assert_eq!(verify::foreign_proof(&foreign, &checkpoints, &stream, &proof, &item), Ok(()));
+///
+/// assert_eq!(verify::foreign_proof(&foreign, &[chain[0].curr_hash()], &stream, &proof, &item), Err(verify::VerifyError::UntrustedCheckpoint));
+/// assert_eq!(verify::foreign_proof(&foreign, &checkpoints, &stream, &proof, &[0; BLOCKHASHLEN]), Err(verify::VerifyError::NotIncluded));
+/// let local = ChainParams { chain_id: 1, ..foreign };
+/// assert_eq!(verify::foreign_proof(&local, &checkpoints, &stream, &proof, &item), Err(verify::VerifyError::ChainMismatch(9)));
+/// let skipped = encode_headers(&[headers[0], headers[2]]);
+/// assert_eq!(verify::foreign_proof(&foreign, &checkpoints, &skipped, &proof, &item), Err(verify::VerifyError::Unlinked));
+/// ```
+pub fn foreign_proof<T: Byteable>(params: &ChainParams, checkpoints: &[Hash256], header_bytes: &[u8], proof: &InclusionProof, item: &T) -> Result<(), VerifyError> {
+    let headers = decode_headers(header_bytes).map_err(VerifyError::BadHeaders)?;
+    let checkpoint = headers.first().ok_or(VerifyError::UntrustedCheckpoint)?;
+    if !checkpoints.contains(&checkpoint.hash()) { return Err(VerifyError::UntrustedCheckpoint); }
+    for (position, header) in headers.iter().enumerate() {
+        if header.chain_id != params.chain_id { return Err(VerifyError::ChainMismatch(header.chain_id)); }
+        if position == 0 { continue; }
+        if !header.follows(&headers[position - 1]) { return Err(VerifyError::Unlinked); }
+        if header.target != params.target { return Err(VerifyError::BadTarget); }
+        if !header.meets_target() { return Err(VerifyError::TargetNotMet); }
+    }
+    let last = headers.last().ok_or(VerifyError::UntrustedCheckpoint)?;
+    if !inclusion_proof(proof, item, last) { return Err(VerifyError::NotIncluded); }
+    Ok(())
+}
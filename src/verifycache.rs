@@ -0,0 +1,130 @@
+use std::collections::{HashMap, VecDeque};
+use super::*;
+
+/// Default number of verdicts kept by a [`VerifyCache`].
+pub const DEFAULT_VERIFY_CACHE_SIZE: usize = 4096;
+
+/// Outcome of a successful [`VerifyCache::verify`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verified {
+    /// The block was verified.
+    New,
+    /// A valid block with the same header was verified before. The body of this block
+    /// was not checked: keep the copy verified first.
+    Duplicate,
+}
+
+/// Bounded cache of block verifications, keyed by block hash.
+///
+/// # Description
+///
+/// A block seen several times, such as a gossip duplicate, is only verified once: the
+/// cache remembers the hash of every valid block, so [`verify`](VerifyCache::verify)
+/// reports the next copy as a `Verified::Duplicate` without recomputing its merkle root.
+/// The hash commits to the merkle root and not to the bytes received, so a duplicate is
+/// never reported valid: a copy with another body is simply not needed.
+///
+/// Failures only depending on the header, such as `BlockError::TargetNotMet`, are
+/// cached as well. Failures of the body, `BlockError::MerkleMismatch`, are not: a peer
+/// sending a valid header with a tampered body must not get the valid block rejected.
+///
+/// The cache holds at most `capacity` verdicts and forgets the oldest first.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let data : [i32; 2] = [5, 6];
+/// let block : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 4, 3);
+/// let tampered : BlockchainBlock<i32> = BlockchainBlock::from_parts(*block.header(), BlockBody::new(&data[..1]));
+///
+/// let mut cache = VerifyCache::new(2);
+/// assert_eq!(cache.verify(&tampered), Err(BlockError::MerkleMismatch));
+/// assert_eq!(cache.verify(&block), Ok(Verified::New));
+/// assert_eq!(cache.verify(&block), Ok(Verified::Duplicate));
+/// assert_eq!(cache.verify(&tampered), Ok(Verified::Duplicate));
+///
+/// let unmined : BlockchainBlock<i32> = BlockchainBlock::builder().target([0; BLOCKHASHLEN]).data(&data).build();
+/// assert_eq!(cache.verify(&unmined), Err(BlockError::TargetNotMet));
+/// assert_eq!(cache.verify(&unmined), Err(BlockError::TargetNotMet));
+/// assert_eq!(cache.len(), 2);
+///
+/// let other : BlockchainBlock<i32> = BlockchainBlock::new(None, &data, 5, 3);
+/// assert_eq!(cache.verify(&other), Ok(Verified::New));
+/// assert_eq!(cache.verify(&block), Ok(Verified::New));
+/// ```
+pub struct VerifyCache {
+    capacity: usize,
+    /// hashes of the cached verdicts, oldest first
+    order: VecDeque<Hash256>,
+    verdicts: HashMap<Hash256, Result<(), BlockError>>,
+}
+
+impl VerifyCache {
+    /// Constructs an empty `VerifyCache` holding at most `capacity` verdicts.
+    pub fn new(capacity: usize) -> VerifyCache {
+        VerifyCache { capacity, order: VecDeque::new(), verdicts: HashMap::new() }
+    }
+
+    /// Number of cached verdicts.
+    pub fn len(&self) -> usize {
+        self.verdicts.len()
+    }
+
+    /// Check the cache holds no verdict.
+    pub fn is_empty(&self) -> bool {
+        self.verdicts.is_empty()
+    }
+
+    /// Check a valid block with hash `hash` was verified, so a block with this hash can
+    /// be dropped as a duplicate before decoding its data.
+    pub fn is_valid(&self, hash: &Hash256) -> bool {
+        self.verdicts.get(hash) == Some(&Ok(()))
+    }
+
+    /// Verify `block` as [`BlockchainBlock::verify`] does, unless a block with the same
+    /// hash was verified before.
+    pub fn verify<T, H>(&mut self, block: &BlockchainBlock<T, H>) -> Result<Verified, BlockError>
+    where
+        T: Byteable + Clone,
+        H: Byteable
+    {
+        // the hash of the header, not the one the block holds, so a block claiming the
+        // hash of another cannot give it its verdict
+        let hash = block.header().hash();
+        match self.verdicts.get(&hash) {
+            Some(Ok(())) => return Ok(Verified::Duplicate),
+            Some(Err(error)) => return Err(error.clone()),
+            None => {},
+        }
+        match block.verify() {
+            Ok(()) => {
+                self.insert(hash, Ok(()));
+                Ok(Verified::New)
+            },
+            Err(error) => {
+                if !matches!(error, BlockError::MerkleMismatch | BlockError::HashMismatch) {
+                    self.insert(hash, Err(error.clone()));
+                }
+                Err(error)
+            },
+        }
+    }
+
+    fn insert(&mut self, hash: Hash256, verdict: Result<(), BlockError>) {
+        if self.capacity == 0 { return; }
+        while self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() { self.verdicts.remove(&oldest); }
+        }
+        self.order.push_back(hash);
+        self.verdicts.insert(hash, verdict);
+    }
+}
+
+impl Default for VerifyCache {
+    fn default() -> VerifyCache {
+        VerifyCache::new(DEFAULT_VERIFY_CACHE_SIZE)
+    }
+}
@@ -0,0 +1,188 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Add;
+use super::*;
+use crate::hex;
+
+/// Amount of work needed to find a hash meeting a difficulty target, as an unsigned
+/// 256-bit integer.
+///
+/// The work of a target is `2^256 / (target + 1)`, the expected number of hashes to
+/// try before one does not exceed the target. Work adds up along a chain and saturates
+/// at `Work::MAX`; comparing accumulated work is the base of fork choice by most work.
+///
+/// # Examples
+///
+/// ```
+/// extern crate blockchainblock;
+/// use crate::blockchainblock::*;
+///
+/// let mut target : BlockHash = MAX_TARGET;
+/// target[0] = 0x0f;
+/// let easy : BlockchainBlock<i32> = BlockchainBlock::new(None, &[5], 4, 3);
+/// let hard : BlockchainBlock<i32> = BlockchainBlock::builder().target(target).push(5).build();
+/// assert_eq!(easy.work(), Work::from(1));
+/// assert_eq!(hard.work(), Work::from(16));
+/// assert!(hard.work() > easy.work());
+///
+/// let chain_work : Work = [easy.work(), hard.work()].iter().copied().sum();
+/// assert_eq!(chain_work, Work::from(17));
+/// assert_eq!(chain_work.cmp(&Work::from(17)), std::cmp::Ordering::Equal);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Work {
+    /// 64-bit limbs, most significant first
+    limbs: [u64; 4],
+}
+
+impl Work {
+    /// No work.
+    pub const ZERO: Work = Work { limbs: [0; 4] };
+    /// Largest amount of work, where sums saturate.
+    pub const MAX: Work = Work { limbs: [u64::MAX; 4] };
+
+    /// Work needed to meet `target`, read as a 256-bit big-endian integer.
+    /// A zero target can not be met by any hash but the zero one and gives `Work::MAX`.
+    pub fn from_target(target: &BlockHash) -> Work {
+        let target = Work::from_be_bytes(target);
+        if target == Work::MAX { return Work::from(1); }
+        if target == Work::ZERO { return Work::MAX; }
+        // 2^256 / (target + 1) == (2^256 - target - 1) / (target + 1) + 1
+        let divisor = target.wrapping_add(&Work::from(1));
+        target.not().div(&divisor).wrapping_add(&Work::from(1))
+    }
+
+    /// Read a 256-bit big-endian integer.
+    pub fn from_be_bytes(bytes: &BlockHash) -> Work {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+            let mut value = [0u8; 8];
+            value.copy_from_slice(chunk);
+            *limb = u64::from_be_bytes(value);
+        }
+        Work { limbs }
+    }
+
+    /// Write the work as a 256-bit big-endian integer.
+    pub fn to_be_bytes(&self) -> BlockHash {
+        let mut bytes : BlockHash = [0; BLOCKHASHLEN];
+        for (chunk, limb) in bytes.chunks_mut(8).zip(self.limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Add two amounts of work, returning `None` on overflow.
+    pub fn checked_add(&self, other: &Work) -> Option<Work> {
+        let (sum, carry) = self.overflowing_add(other);
+        if carry { None } else { Some(sum) }
+    }
+
+    /// Add two amounts of work, saturating at `Work::MAX`.
+    pub fn saturating_add(&self, other: &Work) -> Work {
+        self.checked_add(other).unwrap_or(Work::MAX)
+    }
+
+    fn overflowing_add(&self, other: &Work) -> (Work, bool) {
+        let mut limbs = [0u64; 4];
+        let mut carry = false;
+        for i in (0..4).rev() {
+            let (sum, carry_a) = self.limbs[i].overflowing_add(other.limbs[i]);
+            let (sum, carry_b) = sum.overflowing_add(u64::from(carry));
+            limbs[i] = sum;
+            carry = carry_a || carry_b;
+        }
+        (Work { limbs }, carry)
+    }
+
+    fn wrapping_add(&self, other: &Work) -> Work {
+        self.overflowing_add(other).0
+    }
+
+    fn wrapping_sub(&self, other: &Work) -> Work {
+        let mut limbs = [0u64; 4];
+        let mut borrow = false;
+        for i in (0..4).rev() {
+            let (diff, borrow_a) = self.limbs[i].overflowing_sub(other.limbs[i]);
+            let (diff, borrow_b) = diff.overflowing_sub(u64::from(borrow));
+            limbs[i] = diff;
+            borrow = borrow_a || borrow_b;
+        }
+        Work { limbs }
+    }
+
+    fn not(&self) -> Work {
+        let mut limbs = self.limbs;
+        for limb in limbs.iter_mut() { *limb = !*limb; }
+        Work { limbs }
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (self.limbs[3 - index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    /// Shift left by one bit, returning the bit shifted out.
+    fn shl1(&mut self) -> bool {
+        let out = self.limbs[0] >> 63 == 1;
+        for i in 0..4 {
+            let next = if i < 3 { self.limbs[i + 1] >> 63 } else { 0 };
+            self.limbs[i] = (self.limbs[i] << 1) | next;
+        }
+        out
+    }
+
+    /// Long division, `divisor` must not be zero.
+    fn div(&self, divisor: &Work) -> Work {
+        let mut quotient = Work::ZERO;
+        let mut remainder = Work::ZERO;
+        for index in (0..256).rev() {
+            let overflow = remainder.shl1();
+            if self.bit(index) { remainder.limbs[3] |= 1; }
+            if overflow || remainder >= *divisor {
+                remainder = remainder.wrapping_sub(divisor);
+                quotient.limbs[3 - index / 64] |= 1 << (index % 64);
+            }
+        }
+        quotient
+    }
+}
+
+impl From<u64> for Work {
+    fn from(value: u64) -> Work {
+        Work { limbs: [0, 0, 0, value] }
+    }
+}
+
+impl Ord for Work {
+    fn cmp(&self, other: &Work) -> Ordering {
+        self.limbs.cmp(&other.limbs)
+    }
+}
+
+impl PartialOrd for Work {
+    fn partial_cmp(&self, other: &Work) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Saturating addition.
+impl Add for Work {
+    type Output = Work;
+
+    fn add(self, other: Work) -> Work {
+        self.saturating_add(&other)
+    }
+}
+
+impl std::iter::Sum for Work {
+    fn sum<I: Iterator<Item = Work>>(iter: I) -> Work {
+        iter.fold(Work::ZERO, |total, work| total + work)
+    }
+}
+
+/// Lowercase hexadecimal of the 256-bit big-endian integer.
+impl fmt::Display for Work {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", hex::to_hex(&self.to_be_bytes()))
+    }
+}